@@ -0,0 +1,557 @@
+use std::{
+	fs,
+	net::SocketAddr,
+	path::{Path, PathBuf},
+	process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+	conn::{
+		directory::{self, Manifest, SchemaDir},
+		fixture::{self, Fixture},
+		DBClient,
+	},
+	error::{IError, IResult},
+	mapping::{
+		database::DbType,
+		ddl_parser,
+		diff::{self, ColumnDiff},
+		table::Table,
+	},
+	spec::SyncSpec,
+	store::{
+		conn_conf::{ConnConf, ConnSummary},
+		snapshot, SqliteStore, Store,
+	},
+};
+
+/// Exit code of `sync` when target differs from source and `--apply` isn't set.
+pub const DRIFT_EXIT_CODE: u8 = 2;
+
+#[derive(Debug, Parser)]
+#[command(name = "schema-syncr", version, about = "Sync table schemas between databases")]
+pub struct Cli {
+	/// Application directory, overrides the `SCHEMA_SYNCR_HOME` environment variable
+	#[arg(long, global = true)]
+	pub home: Option<PathBuf>,
+
+	/// Output format of commands
+	#[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+	pub format: Format,
+
+	/// Start the GUI if no command is given
+	#[command(subcommand)]
+	pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+	Text,
+	/// Stable JSON documented on the serialized types, requires the `json` feature
+	Json,
+}
+
+#[derive(serde::Serialize)]
+struct ConvertView<'a> {
+	dialect: DbType,
+	sql: &'a str,
+}
+
+#[derive(serde::Serialize)]
+struct PlanView<'a> {
+	statements: &'a [String],
+}
+
+#[derive(serde::Serialize)]
+struct RecordView<'a> {
+	path: &'a Path,
+	tables: usize,
+}
+
+#[derive(serde::Serialize)]
+struct SnapshotView {
+	id: i64,
+	taken_at: String,
+	database: String,
+	table: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+	/// List stored connections
+	ListConns,
+	/// Print columns of a table
+	Describe {
+		/// Connection name, uuid, fixture file or schema directory
+		#[arg(long)]
+		conn: String,
+		database: String,
+		table: String,
+		/// Dialect used to print column types
+		#[arg(long, default_value_t = DbType::Internal)]
+		dialect: DbType,
+	},
+	/// Print the CREATE TABLE statement of a table in another dialect
+	Convert {
+		/// Connection name, uuid, fixture file or schema directory
+		#[arg(long, required_unless_present = "from_file", conflicts_with = "from_file")]
+		conn: Option<String>,
+		/// DDL dump to convert without a connection, e.g. the output of `mysqldump --no-data`
+		#[arg(long)]
+		from_file: Option<PathBuf>,
+		/// Dialect of the DDL dump
+		#[arg(long, default_value_t = DbType::MySQL)]
+		from: DbType,
+		/// Required with `--conn`, only converts tables of this database with `--from-file`
+		database: Option<String>,
+		/// Required with `--conn`, only converts this table with `--from-file`
+		table: Option<String>,
+		/// Target dialect, e.g. clickhouse
+		#[arg(long)]
+		to: DbType,
+	},
+	/// Show column differences between a source and a target table
+	Diff(SyncArgs),
+	/// Print the statements which make target table consistent with source table
+	Plan(SyncArgs),
+	/// Execute the statements printed by `plan` on the target connection
+	Apply(SyncArgs),
+	/// Plan all tables described by a sync spec file, exits with code 2 on drift
+	Sync {
+		/// Spec file in TOML or YAML format
+		spec: PathBuf,
+		/// Execute the planned statements instead of failing on drift
+		#[arg(long)]
+		apply: bool,
+	},
+	/// Capture databases, tables and columns of a connection into a fixture file or a schema
+	/// directory
+	Record {
+		/// Connection name or uuid
+		#[arg(long)]
+		conn: String,
+		/// Fixture file, written in TOML, YAML or JSON according to its extension, or schema
+		/// directory when it has no extension
+		out: PathBuf,
+		/// Database to capture, can be repeated, all databases if omitted
+		#[arg(long = "database")]
+		databases: Vec<String>,
+	},
+	/// Snapshot the tables of a connection which changed since their latest snapshot, requires
+	/// the `json` feature
+	Snapshot {
+		/// Connection name, uuid, fixture file or schema directory
+		#[arg(long)]
+		conn: String,
+		/// Database to snapshot, can be repeated, all databases if omitted
+		#[arg(long = "database")]
+		databases: Vec<String>,
+	},
+	/// Serve the HTTP API, requires the `server` feature
+	Serve {
+		#[arg(long, default_value = "127.0.0.1:7878")]
+		addr: SocketAddr,
+	},
+}
+
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+	/// Source connection name, uuid, fixture file or schema directory
+	#[arg(long)]
+	pub source: String,
+	/// Target connection name, uuid, fixture file or schema directory
+	#[arg(long)]
+	pub target: String,
+	pub database: String,
+	pub table: String,
+	/// Database on target, defaults to source database
+	#[arg(long)]
+	pub target_database: Option<String>,
+	/// Table on target, defaults to source table
+	#[arg(long)]
+	pub target_table: Option<String>,
+	/// Also drop columns which only exist in target
+	#[arg(long)]
+	pub drop_removed: bool,
+}
+
+struct SyncPlan {
+	target_conf: ConnConf,
+	target_client: DBClient,
+	target: Table,
+	diffs: Vec<ColumnDiff>,
+	statements: Vec<String>,
+}
+
+/// Run `command`, returns the exit code of the process.
+pub fn run(command: Command, format: Format) -> IResult<ExitCode> {
+	let store = &SqliteStore;
+	match command {
+		Command::ListConns => list_conns(store, format),
+		Command::Describe { conn, database, table, dialect } => {
+			describe(store, &conn, &database, &table, dialect, format)
+		}
+		Command::Convert { from_file: Some(path), from, database, table, to, .. } => {
+			convert_file(&path, from, database.as_ref(), table.as_ref(), to, format)
+		}
+		Command::Convert { conn, from_file: None, database, table, to, .. } => {
+			match (conn, database, table) {
+				(Some(conn), Some(database), Some(table)) => {
+					convert(store, &conn, &database, &table, to, format)
+				}
+				_ => Err(IError::PromptError(
+					"Convert needs a database and a table with --conn".to_owned(),
+				)),
+			}
+		}
+		Command::Diff(args) => print_diffs(&build_plan(store, &args)?, format),
+		Command::Plan(args) => print_statements(&build_plan(store, &args)?.statements, format),
+		Command::Apply(args) => apply(store, &build_plan(store, &args)?, format),
+		Command::Sync { spec, apply } => return sync(store, &spec, apply, format),
+		Command::Record { conn, out, databases } => record(store, &conn, &out, &databases, format),
+		Command::Snapshot { conn, databases } => take_snapshots(store, &conn, &databases, format),
+		Command::Serve { addr } => serve(addr),
+	}?;
+	Ok(ExitCode::SUCCESS)
+}
+
+fn list_conns(store: &dyn Store, format: Format) -> IResult<()> {
+	let conns = store.list_conns()?.into_iter().map(ConnSummary::from).collect::<Vec<_>>();
+	match format {
+		Format::Text => conns.iter().for_each(|conn| {
+			println!("{:<38} {:<24} {:<12} {}", conn.uuid, conn.name, conn.r#type, conn.url)
+		}),
+		Format::Json => print_json(&conns)?,
+	}
+
+	Ok(())
+}
+
+fn describe(
+	store: &dyn Store,
+	conn: &str,
+	database: &String,
+	table: &String,
+	dialect: DbType,
+	format: Format,
+) -> IResult<()> {
+	let (_, client) = client_of(store, conn)?;
+	let table = client.table(database, table)?;
+	match format {
+		Format::Text => table.columns.iter().for_each(|column| {
+			println!(
+				"{:<32} {:<40} {}",
+				column.name,
+				column.r#type.to_type(dialect),
+				column.comment
+			)
+		}),
+		Format::Json => print_json(&table)?,
+	}
+
+	Ok(())
+}
+
+fn convert(
+	store: &dyn Store,
+	conn: &str,
+	database: &String,
+	table: &String,
+	to: DbType,
+	format: Format,
+) -> IResult<()> {
+	let (_, client) = client_of(store, conn)?;
+	let sql = client.table(database, table)?.to_create_sql(to)?;
+	match format {
+		Format::Text => println!("{};", sql),
+		Format::Json => print_json(&ConvertView { dialect: to, sql: &sql })?,
+	}
+
+	Ok(())
+}
+
+/// Convert the tables created in the DDL dump at `path`, printed in the order of the dump.
+fn convert_file(
+	path: &Path,
+	from: DbType,
+	database: Option<&String>,
+	table: Option<&String>,
+	to: DbType,
+	format: Format,
+) -> IResult<()> {
+	let tables = ddl_parser::parse_create_tables(&fs::read_to_string(path)?, from)?
+		.into_iter()
+		.filter(|parsed| database.map_or(true, |database| &parsed.database == database))
+		.filter(|parsed| table.map_or(true, |table| &parsed.name == table))
+		.collect::<Vec<_>>();
+	if tables.is_empty() {
+		return Err(IError::NotFoundError(format!("CREATE TABLE in {}", path.display())));
+	}
+
+	let statements =
+		tables.iter().map(|table| table.to_create_sql(to)).collect::<IResult<Vec<_>>>()?;
+	match format {
+		Format::Text => statements.iter().for_each(|sql| println!("{};", sql)),
+		Format::Json => print_json(
+			&statements.iter().map(|sql| ConvertView { dialect: to, sql }).collect::<Vec<_>>(),
+		)?,
+	}
+
+	Ok(())
+}
+
+fn print_diffs(plan: &SyncPlan, format: Format) -> IResult<()> {
+	let dialect = plan.target.r#type;
+	match format {
+		Format::Text => {
+			for diff in &plan.diffs {
+				match diff {
+					ColumnDiff::Added(column) => {
+						println!("+ {:<32} {}", column.name, column.r#type.to_type(dialect))
+					}
+					ColumnDiff::Removed(column) => {
+						println!("- {:<32} {}", column.name, column.r#type.to_type(dialect))
+					}
+					ColumnDiff::Modified { source, target } => println!(
+						"~ {:<32} {} -> {}",
+						source.name,
+						target.r#type.to_type(dialect),
+						source.r#type.to_type(dialect)
+					),
+				}
+			}
+		}
+		Format::Json => print_json(&plan.diffs)?,
+	}
+
+	Ok(())
+}
+
+fn print_statements(statements: &[String], format: Format) -> IResult<()> {
+	match format {
+		Format::Text => statements.iter().for_each(|sql| println!("{};", sql)),
+		Format::Json => print_json(&PlanView { statements })?,
+	}
+
+	Ok(())
+}
+
+fn apply(store: &dyn Store, plan: &SyncPlan, format: Format) -> IResult<()> {
+	print_statements(&plan.statements, format)?;
+	for sql in &plan.statements {
+		plan.target_client.execute_ddl(
+			store,
+			&plan.target_conf.uuid,
+			&plan.target.database,
+			&plan.target.name,
+			sql,
+		)?;
+	}
+
+	if format == Format::Text {
+		println!("{} statement(s) applied.", plan.statements.len());
+	}
+	Ok(())
+}
+
+fn sync(store: &dyn Store, path: &PathBuf, apply: bool, format: Format) -> IResult<ExitCode> {
+	let spec = SyncSpec::load(path)?;
+	let plans = spec.plan(store)?;
+	let drifted = plans.iter().filter(|plan| !plan.statements.is_empty()).collect::<Vec<_>>();
+	match format {
+		Format::Text => {
+			for plan in &drifted {
+				println!("-- {} -> {}", plan.source, plan.target);
+				plan.statements.iter().for_each(|sql| println!("{};", sql));
+			}
+		}
+		Format::Json => print_json(&plans)?,
+	}
+
+	if apply {
+		spec.apply(store, &plans)?;
+		if format == Format::Text {
+			println!("{} table(s) synced.", drifted.len());
+		}
+	} else if !drifted.is_empty() {
+		eprintln!("{} of {} table(s) drifted.", drifted.len(), plans.len());
+		return Ok(ExitCode::from(DRIFT_EXIT_CODE));
+	}
+
+	Ok(ExitCode::SUCCESS)
+}
+
+fn record(
+	store: &dyn Store,
+	conn: &str,
+	out: &Path,
+	databases: &[String],
+	format: Format,
+) -> IResult<()> {
+	let (_, client) = client_of(store, conn)?;
+	let tables = if out.extension().is_none() {
+		let dir = match SchemaDir::open(out) {
+			Ok(dir) => dir,
+			Err(IError::NotFoundError(_)) => SchemaDir::create(
+				out,
+				&Manifest { dialect: Some(client.dialect()?), ..Default::default() },
+			)?,
+			Err(e) => return Err(e),
+		};
+		dir.record(&client, databases)?
+	} else {
+		let fixture = Fixture::record(&client, databases)?;
+		fixture.save(out)?;
+		fixture.tables.len()
+	};
+	match format {
+		Format::Text => println!("{} table(s) recorded to {}.", tables, out.display()),
+		Format::Json => print_json(&RecordView { path: out, tables })?,
+	}
+
+	Ok(())
+}
+
+fn take_snapshots(
+	store: &dyn Store,
+	conn: &str,
+	databases: &[String],
+	format: Format,
+) -> IResult<()> {
+	let (conf, client) = client_of(store, conn)?;
+	let databases = if databases.is_empty() { client.databases()? } else { databases.to_vec() };
+
+	let (mut tables, mut changed) = (0, Vec::new());
+	for database in &databases {
+		for table in client.tables(database)? {
+			tables += 1;
+			let table = client.table(database, &table)?;
+			if let Some(snapshot) = snapshot::take(store, &conf.uuid, &table)? {
+				changed.push(SnapshotView {
+					id: snapshot.id,
+					taken_at: snapshot.taken_at,
+					database: snapshot.database,
+					table: snapshot.table,
+				});
+			}
+		}
+	}
+
+	match format {
+		Format::Text => {
+			for snapshot in &changed {
+				println!("#{:<8} {}.{}", snapshot.id, snapshot.database, snapshot.table);
+			}
+			println!(
+				"{} of {} table(s) changed since their latest snapshot.",
+				changed.len(),
+				tables
+			);
+		}
+		Format::Json => print_json(&changed)?,
+	}
+
+	Ok(())
+}
+
+#[cfg(feature = "server")]
+fn serve(addr: SocketAddr) -> IResult<()> {
+	tokio::runtime::Builder::new_multi_thread()
+		.enable_all()
+		.build()?
+		.block_on(crate::server::serve(addr))
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_: SocketAddr) -> IResult<()> {
+	Err(crate::error::IError::PromptError("Serve requires the `server` feature".to_owned()))
+}
+
+#[cfg(feature = "json")]
+fn print_json<T: serde::Serialize + ?Sized>(value: &T) -> IResult<()> {
+	println!("{}", serde_json::to_string_pretty(value)?);
+	Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn print_json<T: ?Sized>(_: &T) -> IResult<()> {
+	Err(crate::error::IError::PromptError("JSON output requires the `json` feature".to_owned()))
+}
+
+fn build_plan(store: &dyn Store, args: &SyncArgs) -> IResult<SyncPlan> {
+	let (_, source_client) = client_of(store, &args.source)?;
+	let (target_conf, target_client) = client_of(store, &args.target)?;
+
+	let source = source_client.table(&args.database, &args.table)?;
+	let target = target_client.table(
+		args.target_database.as_ref().unwrap_or(&args.database),
+		args.target_table.as_ref().unwrap_or(&args.table),
+	)?;
+	let (diffs, statements) = diff::plan(&source, &target, args.drop_removed)?;
+
+	Ok(SyncPlan { target_conf, target_client, target, diffs, statements })
+}
+
+/// A stored connection, or a fixture or schema directory when `name_or_uuid` is the path of an
+/// existing file or directory.
+fn client_of(store: &dyn Store, name_or_uuid: &str) -> IResult<(ConnConf, DBClient)> {
+	let conf = match store.find_conn(name_or_uuid) {
+		Err(IError::NotFoundError(_)) if Path::new(name_or_uuid).is_file() => {
+			fixture::conn_conf(name_or_uuid)
+		}
+		Err(IError::NotFoundError(_)) if Path::new(name_or_uuid).is_dir() => {
+			directory::conn_conf(name_or_uuid)
+		}
+		result => result?,
+	};
+	let client = DBClient::get_or_init(conf.clone().try_into()?)?;
+	Ok((conf, client))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+
+	use super::{apply, build_plan, Format, SyncArgs};
+	use crate::{
+		conn::directory::{Manifest, SchemaDir},
+		mapping::database::DbType,
+		store::memory::MemoryStore,
+	};
+
+	#[test]
+	fn test_apply_without_source_table() {
+		let temp = std::env::temp_dir();
+		let id = uuid::Uuid::new_v4();
+		let (source, target) =
+			(temp.join(format!("cli_source_{}", id)), temp.join(format!("cli_target_{}", id)));
+		let manifest = Manifest { dialect: Some(DbType::MySQL), ..Default::default() };
+		SchemaDir::create(&source, &manifest).unwrap();
+		SchemaDir::create(&target, &manifest)
+			.unwrap()
+			.execute("CREATE TABLE `shop`.`user` (`id` bigint, `name` varchar(64))")
+			.unwrap();
+		let path = target.join("shop").join("user.sql");
+		let before = fs::read_to_string(&path).unwrap();
+
+		let store = MemoryStore::default();
+		let args = SyncArgs {
+			source: source.to_str().unwrap().to_owned(),
+			target: target.to_str().unwrap().to_owned(),
+			database: "shop".to_owned(),
+			table: "user".to_owned(),
+			target_database: None,
+			target_table: None,
+			drop_removed: true,
+		};
+		let result = build_plan(&store, &args).and_then(|plan| apply(&store, &plan, Format::Text));
+		assert!(result.is_err());
+		assert_eq!(fs::read_to_string(&path).unwrap(), before);
+		assert!(store.histories.lock().unwrap().is_empty());
+
+		fs::remove_dir_all(&source).unwrap();
+		fs::remove_dir_all(&target).unwrap();
+	}
+}
@@ -1,41 +1,172 @@
-use std::path::PathBuf;
+use std::{
+	fmt::{Display, Formatter},
+	fs,
+	path::PathBuf,
+	str::FromStr,
+	sync::RwLock,
+};
 
 use chrono::Local;
 use log::info;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use tracing::Level;
 use tracing_appender::rolling;
 use tracing_log::LogTracer;
-use tracing_subscriber::{fmt::time::FormatTime, EnvFilter};
+use tracing_subscriber::{fmt::time::FormatTime, layer::SubscriberExt, EnvFilter, Layer as _};
 
-use crate::{store, util};
+use crate::{
+	conn::query_log::QueryLogLayer, error::IResult, mapping::database::DbType, store, util,
+};
+
+static SETTINGS: Lazy<RwLock<Settings>> = Lazy::new(|| RwLock::new(Settings::default()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeKind {
+	#[default]
+	Light,
+	Dark,
+}
+
+/// Language of the user interface, see `i18n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Language {
+	#[default]
+	#[serde(rename = "en")]
+	English,
+	#[serde(rename = "zh_CN")]
+	SimplifiedChinese,
+}
+
+impl Language {
+	pub const ALL: &'static [Language] = &[Language::English, Language::SimplifiedChinese];
+}
+
+/// Shown in the language itself.
+impl Display for Language {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Language::English => write!(f, "English"),
+			Language::SimplifiedChinese => write!(f, "简体中文"),
+		}
+	}
+}
+
+impl ThemeKind {
+	pub fn toggled(self) -> ThemeKind {
+		match self {
+			ThemeKind::Light => ThemeKind::Dark,
+			ThemeKind::Dark => ThemeKind::Light,
+		}
+	}
+}
+
+/// User settings persisted in `settings.toml` of the application directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+	pub log_level: String,
+	pub toast_timeout: u64,
+	pub theme: ThemeKind,
+	pub language: Language,
+	pub default_dialect: DbType,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Settings {
+			log_level: "info".to_owned(),
+			toast_timeout: 2,
+			theme: ThemeKind::default(),
+			language: Language::default(),
+			default_dialect: DbType::ClickHouse,
+		}
+	}
+}
+
+pub fn app_init() -> IResult<()> {
+	init_settings(util::app_settings_file())?;
 
-pub(crate) fn app_init() {
 	let log_file = util::app_log_file();
-	util::create_if_not_exist(log_file).unwrap();
-	init_logger(log_file);
+	util::create_if_not_exist(log_file)?;
+	init_logger(log_file, &settings().log_level);
 
 	let db_file = util::app_db_file();
-	util::create_if_not_exist(db_file).unwrap();
-	store::init_db_if_needed().unwrap();
+	util::create_if_not_exist(db_file)?;
+	store::init_db_if_needed()
 }
 
-fn init_logger(log_file: &PathBuf) {
+/// Snapshot of the current settings.
+pub fn settings() -> Settings {
+	SETTINGS.read().unwrap().clone()
+}
+
+/// Language of the settings, cheaper than cloning them all.
+pub fn language() -> Language {
+	SETTINGS.read().unwrap().language
+}
+
+/// Modify the settings and write them back to `settings.toml`.
+pub fn update_settings<F: FnOnce(&mut Settings)>(f: F) -> IResult<()> {
+	let mut settings = SETTINGS.write().unwrap();
+	f(&mut settings);
+	write_settings(util::app_settings_file(), &settings)
+}
+
+fn init_settings(settings_file: &PathBuf) -> IResult<()> {
+	let settings = if util::exists(settings_file) {
+		toml::from_str(&fs::read_to_string(settings_file)?)?
+	} else {
+		let settings = Settings::default();
+		write_settings(settings_file, &settings)?;
+		settings
+	};
+
+	*SETTINGS.write().unwrap() = settings;
+	Ok(())
+}
+
+fn write_settings(settings_file: &PathBuf, settings: &Settings) -> IResult<()> {
+	util::create_if_not_exist(settings_file)?;
+	fs::write(settings_file, toml::to_string_pretty(settings)?)?;
+	Ok(())
+}
+
+fn init_logger(log_file: &PathBuf, log_level: &str) {
 	LogTracer::init().expect("Failed to set logger");
 
 	let log_path = log_file.parent().unwrap();
 	let file = log_file.file_name().unwrap();
 	let file_appender = rolling::daily(log_path, file);
+	let level = Level::from_str(log_level).unwrap_or(Level::INFO);
 
-	let subscriber = tracing_subscriber::fmt()
-		.with_timer(LogFormatter)
-		.with_writer(file_appender)
-		.with_env_filter(EnvFilter::from_default_env().add_directive(Level::INFO.into()))
-		.finish();
+	// The query log is collected whatever the level of the file.
+	let subscriber = tracing_subscriber::registry()
+		.with(
+			tracing_subscriber::fmt::layer()
+				.with_timer(LogFormatter)
+				.with_writer(file_appender)
+				.with_ansi(false)
+				.with_filter(EnvFilter::from_default_env().add_directive(level.into())),
+		)
+		.with(QueryLogLayer);
 
 	tracing::subscriber::set_global_default(subscriber).expect("Failed to set subscriber");
 
 	info!("Use tracing as backend of logger!");
 }
+/// The file currently written by the daily rolling appender, `app.log.<date>`.
+pub fn current_log_file() -> Option<PathBuf> {
+	let log_file = util::app_log_file();
+	let prefix = log_file.file_name()?.to_str()?;
+	fs::read_dir(log_file.parent()?)
+		.ok()?
+		.filter_map(|entry| entry.ok())
+		.filter(|entry| entry.file_name().to_str().map_or(false, |name| name.starts_with(prefix)))
+		.max_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok())
+		.map(|entry| entry.path())
+}
+
 struct LogFormatter;
 
 impl FormatTime for LogFormatter {
@@ -43,3 +174,21 @@ impl FormatTime for LogFormatter {
 		write!(w, "{}", Local::now().format("%Y-%m-%d %H:%M:%S"))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::Settings;
+
+	#[test]
+	fn test_partial_settings() {
+		let settings: Settings =
+			toml::from_str("toast_timeout = 5\ntheme = \"Dark\"\nlanguage = \"zh_CN\"").unwrap();
+		assert_eq!(settings.toast_timeout, 5);
+		assert_eq!(settings.theme, super::ThemeKind::Dark);
+		assert_eq!(settings.language, super::Language::SimplifiedChinese);
+		assert_eq!(settings.log_level, "info");
+
+		let written = toml::to_string_pretty(&Settings::default()).unwrap();
+		assert_eq!(toml::from_str::<Settings>(&written).unwrap(), Settings::default());
+	}
+}
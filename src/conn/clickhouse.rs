@@ -1,11 +1,11 @@
-use super::{ClickHouseRow, DBClient, DBParam, DBQuery};
+use super::{query_log, ClickHouseRow, DBClient, DBExecute, DBParam, DBQuery};
 use crate::{
 	error::{IError, IResult},
 	mapping::database::DbType,
 };
 use clickhouse::{Client, Compression};
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 pub(super) fn create_ch_client(ds: &DBParam) -> IResult<DBClient> {
 	let mut client = Client::default()
@@ -27,7 +27,27 @@ pub(super) fn create_ch_client(ds: &DBParam) -> IResult<DBClient> {
 		return Err(IError::PromptError(format!("Ping ClickHouse failed, uuid: {}", ds.uuid)));
 	}
 
-	Ok(DBClient::ClickHouse(Arc::new(client)))
+	Ok(DBClient::ClickHouse(Arc::new(client), ds.uuid.clone()))
+}
+
+impl DBExecute<{ DbType::DB_CLICK_HOUSE }> for DBClient {
+	fn execute<I: AsRef<str>>(&self, sql: I) -> IResult<()> {
+		match self {
+			DBClient::ClickHouse(client, uuid) => {
+				let start = Instant::now();
+				let result = tokio::runtime::Builder::new_current_thread()
+					.enable_all()
+					.build()
+					.map_err(IError::from)
+					.and_then(
+						|runtime| Ok(runtime.block_on(client.query(sql.as_ref()).execute())?),
+					);
+				query_log::record(uuid, sql.as_ref(), start, &result, |_| 0);
+				result
+			}
+			_ => unreachable!(),
+		}
+	}
 }
 
 impl<T: ClickHouseRow + for<'a> Deserialize<'a>> DBQuery<{ DbType::DB_CLICK_HOUSE }, T>
@@ -35,12 +55,17 @@ impl<T: ClickHouseRow + for<'a> Deserialize<'a>> DBQuery<{ DbType::DB_CLICK_HOUS
 {
 	fn query_list<I: AsRef<str>>(&self, sql: I) -> IResult<Vec<T>> {
 		match self {
-			DBClient::ClickHouse(client) => {
-				let rows = tokio::runtime::Builder::new_current_thread()
+			DBClient::ClickHouse(client, uuid) => {
+				let start = Instant::now();
+				let result = tokio::runtime::Builder::new_current_thread()
 					.enable_all()
-					.build()?
-					.block_on(client.query(sql.as_ref()).fetch_all::<T>())?;
-				Ok(rows)
+					.build()
+					.map_err(IError::from)
+					.and_then(|runtime| {
+						Ok(runtime.block_on(client.query(sql.as_ref()).fetch_all::<T>())?)
+					});
+				query_log::record(uuid, sql.as_ref(), start, &result, Vec::len);
+				result
 			}
 			_ => unreachable!(),
 		}
@@ -48,17 +73,21 @@ impl<T: ClickHouseRow + for<'a> Deserialize<'a>> DBQuery<{ DbType::DB_CLICK_HOUS
 
 	fn query_one<I: AsRef<str>>(&self, sql: I) -> IResult<Option<T>> {
 		match self {
-			DBClient::ClickHouse(client) => {
+			DBClient::ClickHouse(client, uuid) => {
+				let start = Instant::now();
 				let result = tokio::runtime::Builder::new_current_thread()
 					.enable_all()
-					.build()?
-					.block_on(client.query(sql.as_ref()).fetch_one::<T>());
-
-				match result {
-					Ok(row) => Ok(Some(row)),
-					Err(clickhouse::error::Error::RowNotFound) => Ok(None),
-					Err(e) => Err(e)?,
-				}
+					.build()
+					.map_err(IError::from)
+					.and_then(|runtime| {
+						match runtime.block_on(client.query(sql.as_ref()).fetch_one::<T>()) {
+							Ok(row) => Ok(Some(row)),
+							Err(clickhouse::error::Error::RowNotFound) => Ok(None),
+							Err(e) => Err(e.into()),
+						}
+					});
+				query_log::record(uuid, sql.as_ref(), start, &result, |row| row.iter().count());
+				result
 			}
 			_ => unreachable!(),
 		}
@@ -0,0 +1,387 @@
+use std::{
+	collections::BTreeSet,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	error::{IError, IResult},
+	mapping::{
+		column::ColumnSpec,
+		database::DbType,
+		ddl_parser::{self, Statement},
+		table::Table,
+	},
+	store::conn_conf::ConnConf,
+	util,
+};
+
+use super::DBClient;
+
+/// Settings of a schema directory, read from this file at its root.
+pub const MANIFEST: &str = "schema.toml";
+
+/// Tables kept in a directory with one file per table per database, e.g.
+///
+/// ```text
+/// schemas/
+/// ├── schema.toml      dialect = "MySQL"
+/// ├── audit/
+/// │   └── event.json   {"name": "event", "database": "audit", ...}
+/// └── shop/
+///     └── user.sql     CREATE TABLE `user` (...);
+/// ```
+///
+/// `.sql` files hold a `CREATE TABLE` statement in the dialect of the directory, `.json` files the
+/// JSON shape of `Table`. Tables are always written the same way, so that an unchanged table keeps
+/// the content of its file and a changed one gives a small diff.
+#[derive(Debug, Clone)]
+pub struct SchemaDir {
+	root: PathBuf,
+	manifest: Manifest,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+	/// Dialect of the `.sql` files and of the statements applied to the directory, MySQL if
+	/// absent.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub dialect: Option<DbType>,
+	/// Format of the files of new tables, existing files keep theirs.
+	#[serde(default)]
+	pub format: TableFormat,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+	#[default]
+	Sql,
+	Json,
+}
+
+impl TableFormat {
+	const ALL: [TableFormat; 2] = [TableFormat::Sql, TableFormat::Json];
+
+	fn extension(self) -> &'static str {
+		match self {
+			TableFormat::Sql => "sql",
+			TableFormat::Json => "json",
+		}
+	}
+}
+
+impl SchemaDir {
+	/// The existing directory at `root`, without a manifest its tables are MySQL ones in `.sql`
+	/// files.
+	pub fn open(root: &Path) -> IResult<SchemaDir> {
+		if !root.is_dir() {
+			return Err(IError::NotFoundError(format!("schema directory {}", root.display())));
+		}
+
+		let manifest_path = root.join(MANIFEST);
+		let manifest: Manifest = if manifest_path.is_file() {
+			toml::from_str(&fs::read_to_string(manifest_path)?)?
+		} else {
+			Manifest::default()
+		};
+		match manifest.dialect {
+			None | Some(DbType::MySQL | DbType::ClickHouse) => {
+				Ok(SchemaDir { root: root.to_owned(), manifest })
+			}
+			Some(dialect) => Err(IError::PromptError(format!(
+				"Unsupported schema directory dialect {} in {}",
+				dialect,
+				root.display()
+			))),
+		}
+	}
+
+	/// Create the directory at `root` if needed and write its manifest.
+	pub fn create(root: &Path, manifest: &Manifest) -> IResult<SchemaDir> {
+		util::write_file(&root.join(MANIFEST), &toml::to_string_pretty(manifest)?)?;
+		SchemaDir::open(root)
+	}
+
+	/// Write the tables of `databases` in `client`, all databases if empty. Returns how many
+	/// tables were written.
+	pub fn record(&self, client: &DBClient, databases: &[String]) -> IResult<usize> {
+		let databases = if databases.is_empty() { client.databases()? } else { databases.to_vec() };
+
+		let mut written = 0;
+		for database in &databases {
+			for table in client.tables(database)? {
+				self.write_table(&client.table(database, &table)?)?;
+				written += 1;
+			}
+		}
+		Ok(written)
+	}
+
+	pub fn dialect(&self) -> DbType {
+		self.manifest.dialect.unwrap_or(DbType::MySQL)
+	}
+
+	/// Sub directories, sorted by name.
+	pub fn databases(&self) -> IResult<Vec<String>> {
+		let mut databases = Vec::new();
+		for entry in fs::read_dir(&self.root)? {
+			let entry = entry?;
+			let name = entry.file_name().to_string_lossy().into_owned();
+			if entry.file_type()?.is_dir() && !name.starts_with('.') {
+				databases.push(name);
+			}
+		}
+		databases.sort();
+		Ok(databases)
+	}
+
+	/// Tables of `database` sorted by name, none if it doesn't exist.
+	pub fn tables(&self, database: &str) -> IResult<Vec<String>> {
+		let dir = self.root.join(checked(database)?);
+		if !dir.is_dir() {
+			return Ok(vec![]);
+		}
+
+		let mut tables = BTreeSet::new();
+		for entry in fs::read_dir(dir)? {
+			let path = entry?.path();
+			let stem = path.file_stem().and_then(|stem| stem.to_str());
+			if let (Some(_), Some(stem)) = (format_of(&path), stem) {
+				tables.insert(stem.to_owned());
+			}
+		}
+		Ok(tables.into_iter().collect())
+	}
+
+	/// `None` if the table has no file.
+	pub fn table(&self, database: &str, name: &str) -> IResult<Option<Table>> {
+		let (path, format) = match self.file_of(database, name)? {
+			Some(file) => file,
+			None => return Ok(None),
+		};
+
+		let content = fs::read_to_string(&path)?;
+		let table = match format {
+			TableFormat::Json => from_json(&content)?,
+			TableFormat::Sql => ddl_parser::parse_create_tables(&content, self.dialect())?
+				.into_iter()
+				.next()
+				.ok_or(IError::PromptError(format!(
+					"No CREATE TABLE statement in {}",
+					path.display()
+				)))?,
+		};
+		// The file is named after the table, whatever the statement says.
+		Ok(Some(Table { database: database.to_owned(), name: name.to_owned(), ..table }))
+	}
+
+	/// Columns of the table, none if it has no file so that plans against it create the table.
+	pub fn table_schema(&self, database: &str, name: &str) -> IResult<Vec<ColumnSpec>> {
+		Ok(self.table(database, name)?.map_or(vec![], |table| table.columns))
+	}
+
+	/// Write `table` in the format of its file, or of the manifest for a new table.
+	pub fn write_table(&self, table: &Table) -> IResult<PathBuf> {
+		let (path, format) = match self.file_of(&table.database, &table.name)? {
+			Some(file) => file,
+			None => {
+				let format = self.manifest.format;
+				(self.path_of(&table.database, &table.name, format)?, format)
+			}
+		};
+
+		let content = match format {
+			TableFormat::Json => to_json(table)?,
+			TableFormat::Sql => {
+				// The database is given by the directory.
+				let table = Table { database: String::new(), ..table.clone() };
+				format!("{};\n", table.to_create_sql(self.dialect())?)
+			}
+		};
+		util::write_file(&path, &content)?;
+		Ok(path)
+	}
+
+	/// Apply statements written in the dialect of the directory, such as those of `diff::plan`.
+	/// `CREATE TABLE` writes the table, `ALTER TABLE` rewrites it with the column changes applied.
+	pub fn execute(&self, sql: &str) -> IResult<()> {
+		let statements = ddl_parser::parse(sql, self.dialect())?;
+		if statements.is_empty() {
+			return Err(IError::PromptError(
+				"Schema directories only accept CREATE TABLE and ALTER TABLE".to_owned(),
+			));
+		}
+
+		for statement in statements {
+			let table = match statement {
+				Statement::Create(table) => table,
+				Statement::Alter(alter) => {
+					let mut table = self.table(&alter.database, &alter.name)?.ok_or(
+						IError::NotFoundError(format!("{}.{}", alter.database, alter.name)),
+					)?;
+					alter.apply(&mut table)?;
+					table
+				}
+			};
+			self.write_table(&table)?;
+		}
+		Ok(())
+	}
+
+	fn file_of(&self, database: &str, name: &str) -> IResult<Option<(PathBuf, TableFormat)>> {
+		for format in TableFormat::ALL {
+			let path = self.path_of(database, name, format)?;
+			if path.is_file() {
+				return Ok(Some((path, format)));
+			}
+		}
+		Ok(None)
+	}
+
+	fn path_of(&self, database: &str, name: &str, format: TableFormat) -> IResult<PathBuf> {
+		let file_name = format!("{}.{}", checked(name)?, format.extension());
+		Ok(self.root.join(checked(database)?).join(file_name))
+	}
+}
+
+/// An unsaved connection to the schema directory at `path`.
+pub fn conn_conf(path: &str) -> ConnConf {
+	ConnConf {
+		uuid: format!("directory:{}", path),
+		name: path.to_owned(),
+		db_type: Some(DbType::Directory),
+		url: path.to_owned(),
+		..Default::default()
+	}
+}
+
+pub(super) fn create_directory_client(path: &str, uuid: &str) -> IResult<DBClient> {
+	SchemaDir::open(Path::new(path))?;
+	Ok(DBClient::Directory(path.to_owned(), uuid.to_owned()))
+}
+
+/// `name` if it can be used as a file name.
+fn checked(name: &str) -> IResult<&str> {
+	if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+		return Err(IError::PromptError(format!("Invalid name in a schema directory: {:?}", name)));
+	}
+	Ok(name)
+}
+
+fn format_of(path: &Path) -> Option<TableFormat> {
+	let extension = path.extension()?.to_str()?;
+	TableFormat::ALL.into_iter().find(|format| format.extension() == extension)
+}
+
+#[cfg(feature = "json")]
+fn from_json(content: &str) -> IResult<Table> {
+	Ok(serde_json::from_str(content)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn from_json(_: &str) -> IResult<Table> {
+	Err(IError::PromptError("JSON tables require the `json` feature".to_owned()))
+}
+
+#[cfg(feature = "json")]
+fn to_json(table: &Table) -> IResult<String> {
+	Ok(format!("{}\n", serde_json::to_string_pretty(table)?))
+}
+
+#[cfg(not(feature = "json"))]
+fn to_json(_: &Table) -> IResult<String> {
+	Err(IError::PromptError("JSON tables require the `json` feature".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{fs, path::PathBuf};
+
+	use super::{Manifest, SchemaDir};
+	use crate::{
+		conn::{fixture, DBClient, DBParam},
+		mapping::{database::DbType, diff},
+	};
+
+	const USER_SQL: &str = "CREATE TABLE `user` (\n  `id` BIGINT unsigned COMMENT 'key',\n  \
+	                        `name` VARCHAR(64),\n  PRIMARY KEY (`id`)\n);\n";
+
+	fn temp_path(name: &str) -> PathBuf {
+		std::env::temp_dir().join(format!("{}_{}", name, uuid::Uuid::new_v4()))
+	}
+
+	#[test]
+	fn test_schema_dir() {
+		let root = temp_path("schema_syncr_dir");
+		let manifest = Manifest { dialect: Some(DbType::MySQL), ..Default::default() };
+		let dir = SchemaDir::create(&root, &manifest).unwrap();
+		dir.execute(
+			"CREATE TABLE `shop`.`user` (`id` bigint(20) unsigned NOT NULL COMMENT 'key', \
+			 `name` varchar(64) DEFAULT NULL, PRIMARY KEY (`id`)) ENGINE=InnoDB",
+		)
+		.unwrap();
+		let path = root.join("shop").join("user.sql");
+		assert_eq!(fs::read_to_string(&path).unwrap(), USER_SQL);
+
+		fs::create_dir_all(root.join(".git")).unwrap();
+		assert_eq!(dir.databases().unwrap(), vec!["shop"]);
+		assert_eq!(dir.tables("shop").unwrap(), vec!["user"]);
+		assert!(dir.tables("audit").unwrap().is_empty());
+		assert!(dir.table_schema("shop", "order").unwrap().is_empty());
+
+		// Reading and writing back leaves the file as it is.
+		dir.write_table(&dir.table("shop", "user").unwrap().unwrap()).unwrap();
+		assert_eq!(fs::read_to_string(&path).unwrap(), USER_SQL);
+
+		dir.execute("ALTER TABLE `shop`.`user` ADD COLUMN `email` VARCHAR(128)").unwrap();
+		let columns = dir.table_schema("shop", "user").unwrap();
+		assert_eq!(columns.last().map(|column| column.name.as_str()), Some("email"));
+		dir.execute("ALTER TABLE `shop`.`user` DROP COLUMN `email`").unwrap();
+		assert_eq!(fs::read_to_string(&path).unwrap(), USER_SQL);
+
+		assert!(dir.execute("DROP TABLE `shop`.`user`").is_err());
+		assert!(dir.execute("ALTER TABLE `shop`.`order` DROP COLUMN `id`").is_err());
+		assert!(dir.execute("CREATE TABLE `../user` (`id` int)").is_err());
+		assert!(SchemaDir::open(&root.join("missing")).is_err());
+
+		fs::remove_dir_all(&root).unwrap();
+	}
+
+	#[test]
+	fn test_record_and_diff() {
+		let root = temp_path("schema_syncr_recorded_dir");
+		let source = temp_path("schema_syncr_dir_fixture").with_extension("toml");
+		crate::util::write_file(
+			&source,
+			r#"
+dialect = "ClickHouse"
+tables = [{ database = "logs", name = "hit", columns = [
+	{ name = "id", type = "UInt64" },
+	{ name = "ua", type = "LowCardinality(Nullable(String))", comment = "agent" },
+] }]
+"#,
+		)
+		.unwrap();
+		let conf = fixture::conn_conf(source.to_str().unwrap());
+		let client = DBClient::connect(&DBParam::try_from(conf).unwrap()).unwrap();
+
+		let manifest = Manifest { dialect: Some(DbType::ClickHouse), ..Default::default() };
+		let dir = SchemaDir::create(&root, &manifest).unwrap();
+		assert_eq!(dir.record(&client, &[]).unwrap(), 1);
+
+		let conf = super::conn_conf(root.to_str().unwrap());
+		let recorded = DBClient::connect(&DBParam::try_from(conf).unwrap()).unwrap();
+		let (logs, hit) = ("logs".to_owned(), "hit".to_owned());
+		let target = recorded.table(&logs, &hit).unwrap();
+		assert_eq!(target.r#type, DbType::ClickHouse);
+		let (diffs, statements) =
+			diff::plan(&client.table(&logs, &hit).unwrap(), &target, true).unwrap();
+		assert!(diffs.is_empty() && statements.is_empty(), "{:?}", statements);
+
+		fs::remove_dir_all(&root).unwrap();
+		fs::remove_file(&source).unwrap();
+	}
+}
@@ -0,0 +1,351 @@
+use std::{
+	collections::{BTreeSet, HashMap},
+	fs,
+	path::{Path, PathBuf},
+	sync::{Arc, RwLock},
+	time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	error::{IError, IResult},
+	mapping::{
+		column::{ColumnSpec, DataType},
+		database::DbType,
+	},
+	store::conn_conf::ConnConf,
+	util,
+};
+
+use super::DBClient;
+
+/// Loaded fixtures with the modification time of their file.
+static FIXTURES: Lazy<RwLock<HashMap<PathBuf, (SystemTime, Arc<Fixture>)>>> =
+	Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Databases, tables and columns served without a database server, loaded from a TOML, YAML or
+/// JSON file, e.g.
+///
+/// ```toml
+/// dialect = "MySQL"
+///
+/// [[tables]]
+/// database = "shop"
+/// name = "user"
+/// columns = [
+///     { name = "id", type = "bigint(20) unsigned", comment = "key" },
+///     { name = "name", type = "varchar(64)" },
+/// ]
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+	/// Dialect of the recorded database, tables read from the fixture are of this type.
+	pub dialect: DbType,
+	/// Syntax of the column types, defaults to `dialect`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub types: Option<DbType>,
+	#[serde(default)]
+	pub tables: Vec<FixtureTable>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FixtureTable {
+	pub database: String,
+	pub name: String,
+	#[serde(default)]
+	pub columns: Vec<FixtureColumn>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FixtureColumn {
+	pub name: String,
+	/// Column type written in the syntax of `Fixture::types`.
+	pub r#type: String,
+	#[serde(default)]
+	pub comment: String,
+}
+
+impl Fixture {
+	/// Fails on an unsupported dialect or types, or on the first column type which can't be parsed.
+	pub fn load(path: &Path) -> IResult<Fixture> {
+		let content = fs::read_to_string(path)?;
+		let fixture: Fixture = match extension(path) {
+			Some("yaml") | Some("yml") => serde_yaml::from_str(&content)?,
+			Some("json") => from_json(&content)?,
+			_ => toml::from_str(&content)?,
+		};
+
+		if matches!(fixture.dialect, DbType::Fixture | DbType::Directory | DbType::Unknown) {
+			return Err(IError::PromptError(format!(
+				"Unsupported fixture dialect {} in {}",
+				fixture.dialect,
+				path.display()
+			)));
+		}
+		let types = fixture.types();
+		if !matches!(types, DbType::MySQL | DbType::ClickHouse | DbType::Internal) {
+			return Err(IError::PromptError(format!(
+				"Unsupported fixture types {} in {}",
+				types,
+				path.display()
+			)));
+		}
+		for table in &fixture.tables {
+			for column in &table.columns {
+				DataType::parse(column.r#type.clone(), types).map_err(|e| {
+					IError::PromptError(format!(
+						"{}.{}.{} in {}: {}",
+						table.database,
+						table.name,
+						column.name,
+						path.display(),
+						e
+					))
+				})?;
+			}
+		}
+
+		Ok(fixture)
+	}
+
+	/// Written in the format given by the extension of `path`, TOML by default.
+	pub fn save(&self, path: &Path) -> IResult<()> {
+		let content = match extension(path) {
+			Some("yaml") | Some("yml") => serde_yaml::to_string(self)?,
+			Some("json") => to_json(self)?,
+			_ => toml::to_string_pretty(self)?,
+		};
+		Ok(util::write_file(path, &content)?)
+	}
+
+	/// Capture the tables of `databases` in `client`, all databases if empty. Types are written
+	/// in the internal syntax, which keeps them lossless whatever the dialect.
+	pub fn record(client: &DBClient, databases: &[String]) -> IResult<Fixture> {
+		let databases = if databases.is_empty() { client.databases()? } else { databases.to_vec() };
+
+		let mut tables = Vec::new();
+		for database in &databases {
+			for table in client.tables(database)? {
+				let columns = client
+					.table_schema(database, &table)?
+					.into_iter()
+					.map(|column| FixtureColumn {
+						name: column.name,
+						r#type: column.r#type.to_type(DbType::Internal),
+						comment: column.comment,
+					})
+					.collect();
+				tables.push(FixtureTable { database: database.clone(), name: table, columns });
+			}
+		}
+
+		Ok(Fixture { dialect: client.dialect()?, types: Some(DbType::Internal), tables })
+	}
+
+	pub fn types(&self) -> DbType {
+		self.types.unwrap_or(self.dialect)
+	}
+
+	/// Databases having at least one table, sorted by name.
+	pub fn databases(&self) -> Vec<String> {
+		let databases = self.tables.iter().map(|table| &table.database).collect::<BTreeSet<_>>();
+		databases.into_iter().cloned().collect()
+	}
+
+	pub fn tables(&self, database: &str) -> Vec<String> {
+		self.tables
+			.iter()
+			.filter(|table| table.database == database)
+			.map(|table| table.name.clone())
+			.collect()
+	}
+
+	/// Columns parsed from their type names in the syntax of `types`, the way a live connection
+	/// parses the types read from its catalog.
+	pub fn table_schema(&self, database: &str, table: &str) -> IResult<Vec<ColumnSpec>> {
+		let fixture_table = self
+			.tables
+			.iter()
+			.find(|fixture_table| fixture_table.database == database && fixture_table.name == table)
+			.ok_or(IError::NotFoundError(format!("{}.{}", database, table)))?;
+
+		fixture_table
+			.columns
+			.iter()
+			.map(|column| {
+				ColumnSpec::create(
+					column.name.clone(),
+					column.r#type.clone(),
+					column.comment.clone(),
+					self.types(),
+				)
+			})
+			.collect()
+	}
+}
+
+/// Fixture stored at `path`, read again once its file is modified.
+pub fn open(path: &str) -> IResult<Arc<Fixture>> {
+	let path = PathBuf::from(path);
+	let modified = fs::metadata(&path)?.modified()?;
+	if let Some((loaded_at, fixture)) = FIXTURES.read().unwrap().get(&path) {
+		if *loaded_at == modified {
+			return Ok(fixture.clone());
+		}
+	}
+
+	let fixture = Arc::new(Fixture::load(&path)?);
+	FIXTURES.write().unwrap().insert(path, (modified, fixture.clone()));
+	Ok(fixture)
+}
+
+/// An unsaved connection to the fixture at `path`.
+pub fn conn_conf(path: &str) -> ConnConf {
+	ConnConf {
+		uuid: format!("fixture:{}", path),
+		name: path.to_owned(),
+		db_type: Some(DbType::Fixture),
+		url: path.to_owned(),
+		..Default::default()
+	}
+}
+
+pub(super) fn create_fixture_client(path: &str, uuid: &str) -> IResult<DBClient> {
+	open(path)?;
+	Ok(DBClient::Fixture(path.to_owned(), uuid.to_owned()))
+}
+
+fn extension(path: &Path) -> Option<&str> {
+	path.extension().and_then(|ext| ext.to_str())
+}
+
+#[cfg(feature = "json")]
+fn from_json(content: &str) -> IResult<Fixture> {
+	Ok(serde_json::from_str(content)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn from_json(_: &str) -> IResult<Fixture> {
+	Err(IError::PromptError("JSON fixtures require the `json` feature".to_owned()))
+}
+
+#[cfg(feature = "json")]
+fn to_json(fixture: &Fixture) -> IResult<String> {
+	Ok(serde_json::to_string_pretty(fixture)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn to_json(_: &Fixture) -> IResult<String> {
+	Err(IError::PromptError("JSON fixtures require the `json` feature".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{conn_conf, Fixture};
+	use crate::{
+		conn::{DBClient, DBParam},
+		mapping::database::DbType,
+	};
+
+	const SHOP: &str = r#"
+dialect = "MySQL"
+
+[[tables]]
+database = "shop"
+name = "user"
+columns = [
+	{ name = "id", type = "bigint(20) unsigned", comment = "key" },
+	{ name = "name", type = "varchar(64)" },
+	{ name = "score", type = "decimal(10,2)" },
+]
+
+[[tables]]
+database = "audit"
+name = "event"
+columns = [{ name = "at", type = "datetime" }]
+"#;
+
+	fn internal_types(fixture: &Fixture, database: &str, table: &str) -> Vec<String> {
+		let columns = fixture.table_schema(database, table).unwrap();
+		columns.iter().map(|column| column.r#type.to_type(DbType::Internal)).collect()
+	}
+
+	#[test]
+	fn test_fixture() {
+		let fixture: Fixture = toml::from_str(SHOP).unwrap();
+		assert_eq!(fixture.databases(), vec!["audit", "shop"]);
+		assert_eq!(fixture.tables("shop"), vec!["user"]);
+		assert!(fixture.tables("missing").is_empty());
+		assert_eq!(
+			internal_types(&fixture, "shop", "user"),
+			vec!["uint64", "string(64)", "decimal(10,2)"]
+		);
+		assert_eq!(fixture.table_schema("shop", "user").unwrap()[0].comment, "key");
+		assert!(fixture.table_schema("shop", "order").is_err());
+
+		let clickhouse: Fixture = toml::from_str(
+			r#"
+dialect = "ClickHouse"
+tables = [{ database = "logs", name = "hit", columns = [{ name = "ua", type = "Nullable(String)" }] }]
+"#,
+		)
+		.unwrap();
+		assert_eq!(internal_types(&clickhouse, "logs", "hit"), vec!["nullable<string>"]);
+	}
+
+	#[test]
+	fn test_unsupported_types() {
+		let dir = std::env::temp_dir();
+		for (name, header) in [
+			("dialect", "dialect = \"Fixture\""),
+			("hbase", "dialect = \"MySQL\"\ntypes = \"HBase\""),
+			("directory", "dialect = \"ClickHouse\"\ntypes = \"Directory\""),
+		] {
+			let path = dir.join(format!("schema_syncr_{}_{}.toml", name, uuid::Uuid::new_v4()));
+			crate::util::write_file(&path, header).unwrap();
+			assert!(Fixture::load(&path).is_err(), "{}", header);
+			std::fs::remove_file(&path).unwrap();
+		}
+
+		let path = dir.join(format!("schema_syncr_column_{}.toml", uuid::Uuid::new_v4()));
+		crate::util::write_file(
+			&path,
+			"dialect = \"ClickHouse\"\n[[tables]]\ndatabase = \"logs\"\nname = \"hit\"\n\
+			 columns = [{ name = \"id\", type = \"UInt64\" }, { name = \"ua\", type = \"Strnig\" }]",
+		)
+		.unwrap();
+		let message = Fixture::load(&path).unwrap_err().to_string();
+		assert!(message.contains("logs.hit.ua"), "{}", message);
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn test_record() {
+		let dir = std::env::temp_dir();
+		let id = uuid::Uuid::new_v4();
+		let (source, recorded) = (
+			dir.join(format!("schema_syncr_fixture_{}.toml", id)),
+			dir.join(format!("schema_syncr_recorded_{}.yaml", id)),
+		);
+		crate::util::write_file(&source, SHOP).unwrap();
+
+		let conf = conn_conf(source.to_str().unwrap());
+		let client = DBClient::connect(&DBParam::try_from(conf).unwrap()).unwrap();
+		let table = client.table(&"shop".to_owned(), &"user".to_owned()).unwrap();
+		assert_eq!((table.r#type, table.columns.len()), (DbType::MySQL, 3));
+
+		Fixture::record(&client, &["shop".to_owned()]).unwrap().save(&recorded).unwrap();
+		let replayed = Fixture::load(&recorded).unwrap();
+		assert_eq!((replayed.dialect, replayed.types()), (DbType::MySQL, DbType::Internal));
+		assert_eq!(replayed.databases(), vec!["shop"]);
+		assert_eq!(
+			internal_types(&replayed, "shop", "user"),
+			internal_types(&toml::from_str(SHOP).unwrap(), "shop", "user")
+		);
+
+		std::fs::remove_file(&source).unwrap();
+		std::fs::remove_file(&recorded).unwrap();
+	}
+}
@@ -1,33 +1,54 @@
 use ::clickhouse::Client;
-use ::mysql::{Pool, Row};
+use ::mysql::Pool;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::{
 	borrow::Borrow,
 	collections::HashMap,
+	fmt::Debug,
 	hash::{Hash, Hasher},
+	path::Path,
 	sync::{Arc, RwLock},
+	time::Instant,
 };
 
 use crate::{
 	error::{IError, IResult},
-	mapping::{column::ColumnSpec, database::DbType},
-	store::conn_conf::ConnConf,
+	mapping::{column::ColumnSpec, database::DbType, table::Table},
+	store::{conn_conf::ConnConf, ddl_history, Store},
 };
 
+use self::{directory::SchemaDir, endpoint::Endpoint};
+
 pub use ::clickhouse::Row as ClickHouseRow;
 pub use ::mysql::prelude::FromRow as MysqlRow;
 
 mod clickhouse;
+pub mod directory;
+pub mod endpoint;
+pub mod fixture;
 mod mysql;
+pub mod query_log;
 
 static DB_CLIENTS: Lazy<RwLock<HashMap<DBParam, DBClient>>> =
 	Lazy::new(|| RwLock::new(HashMap::new()));
 
+/// A connected client, tagged with the uuid of its connection.
 #[derive(Clone)]
 pub enum DBClient {
-	ClickHouse(Arc<Client>),
-	Mysql(Pool),
+	ClickHouse(Arc<Client>, String),
+	Mysql(Pool, String),
+	/// Path of a fixture file, see `fixture::Fixture`.
+	Fixture(String, String),
+	/// Path of a schema directory, see `directory::SchemaDir`.
+	Directory(String, String),
+}
+
+/// Outcome of a successful connection test.
+#[derive(Debug, Clone)]
+pub struct ConnTest {
+	pub latency_ms: u128,
+	pub version: String,
 }
 
 impl DBClient {
@@ -35,14 +56,7 @@ impl DBClient {
 		match Self::get(&ds) {
 			Some(cli) => Ok(cli.clone()),
 			None => {
-				let client = match &ds.db_type {
-					&DbType::MySQL => mysql::create_mysql_client(&ds),
-					&DbType::ClickHouse => clickhouse::create_ch_client(&ds),
-					_ => {
-						Err(IError::PromptError(format!("Unsupported db type: {:?}", &ds.db_type)))
-					}
-				};
-
+				let client = Self::connect(&ds);
 				if client.is_ok() {
 					let mut write_lock = DB_CLIENTS.write().unwrap();
 					write_lock.insert(ds, client.as_ref().unwrap().clone());
@@ -58,36 +72,78 @@ impl DBClient {
 		read_lock.get(t.borrow()).map(|pool| pool.clone())
 	}
 
+	/// Drop the cached client, the next `get_or_init` connects with the latest settings.
+	pub fn evict(uuid: &String) {
+		DB_CLIENTS.write().unwrap().remove(uuid);
+	}
+
+	/// Create and ping a client without caching it.
+	pub fn connect(ds: &DBParam) -> IResult<DBClient> {
+		match &ds.db_type {
+			&DbType::MySQL => mysql::create_mysql_client(ds),
+			&DbType::ClickHouse => clickhouse::create_ch_client(ds),
+			&DbType::Fixture => fixture::create_fixture_client(&ds.url, &ds.uuid),
+			&DbType::Directory => directory::create_directory_client(&ds.url, &ds.uuid),
+			_ => Err(IError::PromptError(format!("Unsupported db type: {:?}", &ds.db_type))),
+		}
+	}
+
+	/// Connect with `ds`, measure how long it takes and read the server version.
+	pub fn test(ds: &DBParam) -> IResult<ConnTest> {
+		let start = Instant::now();
+		let client = Self::connect(ds)?;
+		let latency_ms = start.elapsed().as_millis();
+		Ok(ConnTest { latency_ms, version: client.server_version()? })
+	}
+
+	pub fn server_version(&self) -> IResult<String> {
+		let version = match self {
+			DBClient::ClickHouse(..) => {
+				DBQuery::<{ DbType::DB_CLICK_HOUSE }, String>::query_one(self, "select version()")
+			}
+			DBClient::Mysql(..) => {
+				DBQuery::<{ DbType::DB_MYSQL }, String>::query_one(self, "select version()")
+			}
+			DBClient::Fixture(..) => Ok(Some("fixture".to_owned())),
+			DBClient::Directory(..) => Ok(Some("directory".to_owned())),
+		}?;
+		Ok(version.unwrap_or_default())
+	}
+
 	pub fn databases(&self) -> IResult<Vec<String>> {
 		match self {
-			DBClient::ClickHouse(_) => {
+			DBClient::ClickHouse(..) => {
 				DBQuery::<{ DbType::DB_CLICK_HOUSE }, String>::query_list(self, "show databases")
 			}
-			DBClient::Mysql(_) => {
+			DBClient::Mysql(..) => {
 				DBQuery::<{ DbType::DB_MYSQL }, String>::query_list(self, "show databases")
 			}
+			DBClient::Fixture(path, _) => Ok(fixture::open(path)?.databases()),
+			DBClient::Directory(path, _) => SchemaDir::open(Path::new(path))?.databases(),
 		}
 	}
 
 	pub fn tables(&self, database: &String) -> IResult<Vec<String>> {
 		match self {
-			DBClient::ClickHouse(_) => DBQuery::<{ DbType::DB_CLICK_HOUSE }, String>::query_list(
+			DBClient::ClickHouse(..) => DBQuery::<{ DbType::DB_CLICK_HOUSE }, String>::query_list(
 				self,
 				format!("show tables in {}", database),
 			),
-			DBClient::Mysql(_) => DBQuery::<{ DbType::DB_MYSQL }, String>::query_list(
+			DBClient::Mysql(..) => DBQuery::<{ DbType::DB_MYSQL }, String>::query_list(
 				self,
 				format!(
 					"SELECT table_name FROM information_schema.tables WHERE table_schema = '{}'",
 					database
 				),
 			),
+			DBClient::Fixture(path, _) => Ok(fixture::open(path)?.tables(database)),
+			DBClient::Directory(path, _) => SchemaDir::open(Path::new(path))?.tables(database),
 		}
 	}
 
 	pub fn table_schema(&self, database: &String, table: &String) -> IResult<Vec<ColumnSpec>> {
 		match self {
-			DBClient::ClickHouse(_) => {
+			DBClient::ClickHouse(..) => {
 				let column_tuples = DBQuery::<{ DbType::DB_CLICK_HOUSE }, (String, String, String)>::query_list(
 					self,
 					format!("select name, type, comment from system.columns where database = '{}' and table = '{}'", database, table),
@@ -104,29 +160,149 @@ impl DBClient {
 				}
 				Ok(column_specs)
 			}
-			DBClient::Mysql(_) => {
-				let Some(row) = DBQuery::<{ DbType::DB_MYSQL }, Row>::query_one(
-					self,
-					format!("select * from {}.{}", database, table),
-				)? else {
-					return Ok(vec![]);
-				};
+			DBClient::Mysql(..) => {
+				let column_tuples =
+					DBQuery::<{ DbType::DB_MYSQL }, (String, String, String)>::query_list(
+						self,
+						format!(
+							"SELECT column_name, column_type, column_comment \
+							FROM information_schema.columns \
+							WHERE table_schema = '{}' AND table_name = '{}' \
+							ORDER BY ordinal_position",
+							database, table
+						),
+					)?;
 
-				let mut column_specs = Vec::with_capacity(row.columns_ref().len());
-				for column in row.columns_ref() {
+				let mut column_specs = Vec::with_capacity(column_tuples.len());
+				for tuple in column_tuples {
 					column_specs.push(ColumnSpec::create(
-						column.name_str().to_string(),
-						column.column_type(),
-						"".to_owned(),
+						tuple.0,
+						tuple.1,
+						tuple.2,
 						DbType::MySQL,
-					)?)
+					)?);
 				}
 				Ok(column_specs)
 			}
+			DBClient::Fixture(path, _) => fixture::open(path)?.table_schema(database, table),
+			DBClient::Directory(path, _) => {
+				SchemaDir::open(Path::new(path))?.table_schema(database, table)
+			}
+		}
+	}
+
+	/// Dialect of the tables read by this client.
+	pub fn dialect(&self) -> IResult<DbType> {
+		match self {
+			DBClient::ClickHouse(..) => Ok(DbType::ClickHouse),
+			DBClient::Mysql(..) => Ok(DbType::MySQL),
+			DBClient::Fixture(path, _) => Ok(fixture::open(path)?.dialect),
+			DBClient::Directory(path, _) => Ok(SchemaDir::open(Path::new(path))?.dialect()),
+		}
+	}
+
+	pub fn table(&self, database: &String, table: &String) -> IResult<Table> {
+		let columns = self.table_schema(database, table)?;
+		Ok(Table::new(database.clone(), table.clone(), self.dialect()?, columns))
+	}
+
+	/// Execute a DDL statement, every execution is recorded in the DDL history of `store`.
+	pub fn execute_ddl(
+		&self,
+		store: &dyn Store,
+		conn_uuid: &str,
+		database: &str,
+		table: &str,
+		statement: &str,
+	) -> IResult<()> {
+		ddl_history::record(store, conn_uuid, database, table, statement, || {
+			self.execute(statement)
+		})
+	}
+
+	/// Execute a statement without recording it in the DDL history, the query log still gets it.
+	pub fn execute(&self, statement: &str) -> IResult<()> {
+		match self {
+			DBClient::ClickHouse(..) => {
+				DBExecute::<{ DbType::DB_CLICK_HOUSE }>::execute(self, statement)
+			}
+			DBClient::Mysql(..) => DBExecute::<{ DbType::DB_MYSQL }>::execute(self, statement),
+			DBClient::Fixture(..) => {
+				Err(IError::PromptError("Fixture connections are read-only".to_owned()))
+			}
+			DBClient::Directory(path, _) => SchemaDir::open(Path::new(path))?.execute(statement),
 		}
 	}
 }
 
+/// Operations the GUI performs on a connected database.
+pub trait SchemaClient: Send + Sync {
+	fn databases(&self) -> IResult<Vec<String>>;
+
+	fn tables(&self, database: &String) -> IResult<Vec<String>>;
+
+	fn table_schema(&self, database: &String, table: &String) -> IResult<Vec<ColumnSpec>>;
+
+	/// Dialect of the tables read by this client.
+	fn dialect(&self) -> IResult<DbType>;
+
+	/// Execute a statement, callers record it in the DDL history of their store.
+	fn execute(&self, statement: &str) -> IResult<()>;
+}
+
+impl SchemaClient for DBClient {
+	fn databases(&self) -> IResult<Vec<String>> {
+		DBClient::databases(self)
+	}
+
+	fn tables(&self, database: &String) -> IResult<Vec<String>> {
+		DBClient::tables(self, database)
+	}
+
+	fn table_schema(&self, database: &String, table: &String) -> IResult<Vec<ColumnSpec>> {
+		DBClient::table_schema(self, database, table)
+	}
+
+	fn dialect(&self) -> IResult<DbType> {
+		DBClient::dialect(self)
+	}
+
+	fn execute(&self, statement: &str) -> IResult<()> {
+		DBClient::execute(self, statement)
+	}
+}
+
+/// Hands out clients for connections, so that callers can be driven by a fake backend.
+pub trait ClientFactory: Debug + Send + Sync {
+	fn client(&self, ds: DBParam) -> IResult<Arc<dyn SchemaClient>>;
+
+	fn test(&self, ds: &DBParam) -> IResult<ConnTest>;
+
+	fn evict(&self, uuid: &String);
+}
+
+/// Shares the cached `DBClient`s.
+#[derive(Debug, Default)]
+pub struct DBClientFactory;
+
+impl ClientFactory for DBClientFactory {
+	fn client(&self, ds: DBParam) -> IResult<Arc<dyn SchemaClient>> {
+		Ok(Arc::new(DBClient::get_or_init(ds)?))
+	}
+
+	fn test(&self, ds: &DBParam) -> IResult<ConnTest> {
+		DBClient::test(ds)
+	}
+
+	fn evict(&self, uuid: &String) {
+		DBClient::evict(uuid)
+	}
+}
+
+pub trait DBExecute<const DB: u8> {
+	fn execute<I: AsRef<str>>(&self, sql: I) -> IResult<()>;
+}
+
 pub trait DBQuery<const DB: u8, T> {
 	fn query_list<I: AsRef<str>>(&self, sql: I) -> IResult<Vec<T>>;
 
@@ -160,15 +336,31 @@ impl Borrow<String> for DBParam {
 impl TryFrom<ConnConf> for DBParam {
 	type Error = IError;
 	fn try_from(value: ConnConf) -> Result<Self, Self::Error> {
-		Ok(DBParam {
+		let db_type =
+			value.db_type.ok_or(IError::PromptError("db type can't be None".to_string()))?;
+		let mut param = DBParam {
 			uuid: value.uuid,
-			db_type: value
-				.db_type
-				.ok_or(IError::PromptError("db type can't be None".to_string()))?,
+			db_type,
 			url: value.url,
 			user: value.username,
 			password: value.password,
 			..Default::default()
-		})
+		};
+
+		// The ClickHouse client takes database, compression and settings apart from its url.
+		if db_type == DbType::ClickHouse {
+			let endpoint = Endpoint::parse(&param.url);
+			param.url = endpoint.base_url();
+			param.database = endpoint.database;
+			for (key, value) in endpoint.params {
+				if key == "compression" {
+					param.compression = value;
+				} else {
+					param.options.insert(key, value);
+				}
+			}
+		}
+
+		Ok(param)
 	}
 }
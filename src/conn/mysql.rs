@@ -1,14 +1,22 @@
-use mysql::{prelude::Queryable, Opts, Pool};
+use std::time::Instant;
+
+use mysql::{prelude::Queryable, Opts, OptsBuilder, Pool};
 
 use crate::{
 	error::{IError, IResult},
 	mapping::database::DbType,
 };
 
-use super::{DBClient, DBParam, DBQuery, MysqlRow};
+use super::{query_log, DBClient, DBExecute, DBParam, DBQuery, MysqlRow};
 
 pub(super) fn create_mysql_client(ds: &DBParam) -> IResult<DBClient> {
-	let opts = Opts::from_url(&ds.url)?;
+	let mut opts = OptsBuilder::from_opts(Opts::from_url(&ds.url)?);
+	if !ds.user.is_empty() {
+		opts = opts.user(Some(&ds.user));
+	}
+	if !ds.password.is_empty() {
+		opts = opts.pass(Some(&ds.password));
+	}
 	let pool = Pool::new(opts)?;
 
 	{
@@ -18,16 +26,37 @@ pub(super) fn create_mysql_client(ds: &DBParam) -> IResult<DBClient> {
 		}
 	}
 
-	Ok(DBClient::Mysql(pool))
+	Ok(DBClient::Mysql(pool, ds.uuid.clone()))
+}
+
+impl DBExecute<{ DbType::DB_MYSQL }> for DBClient {
+	fn execute<I: AsRef<str>>(&self, sql: I) -> IResult<()> {
+		match self {
+			DBClient::Mysql(pool, uuid) => {
+				let start = Instant::now();
+				let result = pool
+					.get_conn()
+					.and_then(|mut conn| conn.query_drop(sql.as_ref()))
+					.map_err(IError::from);
+				query_log::record(uuid, sql.as_ref(), start, &result, |_| 0);
+				result
+			}
+			_ => unreachable!(),
+		}
+	}
 }
 
 impl<T: MysqlRow> DBQuery<{ DbType::DB_MYSQL }, T> for DBClient {
 	fn query_list<I: AsRef<str>>(&self, sql: I) -> IResult<Vec<T>> {
 		match self {
-			DBClient::Mysql(pool) => {
-				let mut conn = pool.get_conn()?;
-				let res = conn.query::<T, I>(sql)?;
-				Ok(res)
+			DBClient::Mysql(pool, uuid) => {
+				let start = Instant::now();
+				let result = pool
+					.get_conn()
+					.and_then(|mut conn| conn.query::<T, _>(sql.as_ref()))
+					.map_err(IError::from);
+				query_log::record(uuid, sql.as_ref(), start, &result, Vec::len);
+				result
 			}
 			_ => unreachable!(),
 		}
@@ -35,10 +64,14 @@ impl<T: MysqlRow> DBQuery<{ DbType::DB_MYSQL }, T> for DBClient {
 
 	fn query_one<I: AsRef<str>>(&self, sql: I) -> IResult<Option<T>> {
 		match self {
-			DBClient::Mysql(pool) => {
-				let mut conn = pool.get_conn()?;
-				let row = conn.query_first::<T, I>(sql)?;
-				Ok(row)
+			DBClient::Mysql(pool, uuid) => {
+				let start = Instant::now();
+				let result = pool
+					.get_conn()
+					.and_then(|mut conn| conn.query_first::<T, _>(sql.as_ref()))
+					.map_err(IError::from);
+				query_log::record(uuid, sql.as_ref(), start, &result, |row| row.iter().count());
+				result
 			}
 			_ => unreachable!(),
 		}
@@ -0,0 +1,126 @@
+use std::{collections::VecDeque, fmt::Debug, sync::RwLock, time::Instant};
+
+use chrono::Local;
+use once_cell::sync::Lazy;
+use tracing::{
+	field::{Field, Visit},
+	info, warn, Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+use crate::error::IResult;
+
+/// Target of the events recorded for every statement sent by `DBQuery`.
+pub const QUERY_TARGET: &'static str = "sql";
+
+/// Number of statements kept in memory, older ones are only in the log file.
+const CAPACITY: usize = 500;
+
+static RECORDS: Lazy<RwLock<VecDeque<QueryRecord>>> =
+	Lazy::new(|| RwLock::new(VecDeque::with_capacity(CAPACITY)));
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryRecord {
+	pub executed_at: String,
+	/// Uuid of the connection.
+	pub conn: String,
+	pub sql: String,
+	pub duration_ms: u64,
+	pub rows: Option<u64>,
+	pub error: Option<String>,
+}
+
+/// Emit the event of a statement, `rows` counts what it returned.
+pub(super) fn record<T>(
+	conn: &str,
+	sql: &str,
+	start: Instant,
+	result: &IResult<T>,
+	rows: impl FnOnce(&T) -> usize,
+) {
+	let duration_ms = start.elapsed().as_millis() as u64;
+	match result {
+		Ok(value) => {
+			info!(target: QUERY_TARGET, conn, duration_ms, rows = rows(value) as u64, sql)
+		}
+		Err(e) => warn!(target: QUERY_TARGET, conn, duration_ms, error = %e, sql),
+	}
+}
+
+/// Recorded statements, newest first.
+pub fn records() -> Vec<QueryRecord> {
+	RECORDS.read().unwrap().iter().rev().cloned().collect()
+}
+
+/// Collects the events of `QUERY_TARGET` for the query log of the GUI.
+pub struct QueryLogLayer;
+
+impl<S: Subscriber> Layer<S> for QueryLogLayer {
+	fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+		if event.metadata().target() != QUERY_TARGET {
+			return;
+		}
+
+		let mut record = QueryRecord {
+			executed_at: Local::now().format("%H:%M:%S").to_string(),
+			..Default::default()
+		};
+		event.record(&mut record);
+
+		let mut records = RECORDS.write().unwrap();
+		if records.len() == CAPACITY {
+			records.pop_front();
+		}
+		records.push_back(record);
+	}
+}
+
+impl Visit for QueryRecord {
+	fn record_u64(&mut self, field: &Field, value: u64) {
+		match field.name() {
+			"duration_ms" => self.duration_ms = value,
+			"rows" => self.rows = Some(value),
+			_ => {}
+		}
+	}
+
+	fn record_str(&mut self, field: &Field, value: &str) {
+		match field.name() {
+			"conn" => self.conn = value.to_owned(),
+			"sql" => self.sql = value.to_owned(),
+			"error" => self.error = Some(value.to_owned()),
+			_ => {}
+		}
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+		self.record_str(field, &format!("{:?}", value));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Instant;
+
+	use tracing_subscriber::layer::SubscriberExt;
+
+	use super::{record, records, QueryLogLayer};
+	use crate::error::IError;
+
+	#[test]
+	fn test_query_log() {
+		let subscriber = tracing_subscriber::registry().with(QueryLogLayer);
+		tracing::subscriber::with_default(subscriber, || {
+			record("uuid", "show databases", Instant::now(), &Ok(vec![1, 2]), Vec::len);
+			let failed: Result<(), _> = Err(IError::NotFoundError("t".to_owned()));
+			record("uuid", "select 1", Instant::now(), &failed, |_| 0);
+			tracing::info!("unrelated");
+		});
+
+		let records = records();
+		assert_eq!(records.len(), 2);
+		assert_eq!((records[0].sql.as_str(), records[0].rows), ("select 1", None));
+		assert_eq!(records[0].error.as_deref(), Some("Not found: t"));
+		assert_eq!((records[1].conn.as_str(), records[1].rows), ("uuid", Some(2)));
+	}
+}
@@ -1,3 +1,5 @@
+use crate::i18n::t;
+
 pub type IResult<T> = core::result::Result<T, crate::error::IError>;
 
 #[derive(Debug, thiserror::Error)]
@@ -17,18 +19,101 @@ pub enum IError {
 	#[error("Sqlite error: {0}")]
 	SqliteError(#[from] rusqlite::Error),
 
+	#[cfg(feature = "gui")]
 	#[error("Iced error: {0}")]
 	IcedError(#[from] iced::Error),
 
 	#[error("Validate error: {0}")]
 	ValidateError(#[from] validator::ValidationErrors),
 
+	#[error("Parse toml error: {0}")]
+	TomlDeError(#[from] toml::de::Error),
+
+	#[error("Write toml error: {0}")]
+	TomlSerError(#[from] toml::ser::Error),
+
+	#[cfg(feature = "json")]
+	#[error("Json error: {0}")]
+	JsonError(#[from] serde_json::Error),
+
+	#[error("Parse yaml error: {0}")]
+	YamlError(#[from] serde_yaml::Error),
+
+	#[error("Regex error: {0}")]
+	RegexError(#[from] regex::Error),
+
 	#[error("Parse number error: {0}")]
 	ParseIntError(#[from] core::num::ParseIntError),
 
+	#[error("Not found: {0}")]
+	NotFoundError(String),
+
 	#[error("Prompt error: {0}")]
 	PromptError(String),
 
 	#[error(transparent)]
 	AnyError(#[from] anyhow::Error),
 }
+
+impl IError {
+	/// Message of this error followed by those of its sources not already included in it.
+	pub fn chain(&self) -> String {
+		self.with_sources(self.to_string())
+	}
+
+	/// Same as `chain` with the message translated into the language of the settings.
+	pub fn localized(&self) -> String {
+		let (key, detail) = match self {
+			IError::CHError(e) => ("error.clickhouse", e.to_string()),
+			IError::IOError(e) => ("error.io", e.to_string()),
+			IError::MysqlError(e) => ("error.mysql", e.to_string()),
+			IError::MysqlUrlError(e) => ("error.mysql_url", e.to_string()),
+			IError::SqliteError(e) => ("error.sqlite", e.to_string()),
+			#[cfg(feature = "gui")]
+			IError::IcedError(e) => ("error.iced", e.to_string()),
+			IError::ValidateError(e) => ("error.validate", e.to_string()),
+			IError::TomlDeError(e) => ("error.toml_de", e.to_string()),
+			IError::TomlSerError(e) => ("error.toml_ser", e.to_string()),
+			#[cfg(feature = "json")]
+			IError::JsonError(e) => ("error.json", e.to_string()),
+			IError::YamlError(e) => ("error.yaml", e.to_string()),
+			IError::RegexError(e) => ("error.regex", e.to_string()),
+			IError::ParseIntError(e) => ("error.parse_int", e.to_string()),
+			IError::NotFoundError(detail) => ("error.not_found", detail.clone()),
+			IError::PromptError(detail) => ("error.prompt", detail.clone()),
+			IError::AnyError(e) => ("error.any", e.to_string()),
+		};
+
+		self.with_sources(t!(key, detail = detail))
+	}
+
+	fn with_sources(&self, mut message: String) -> String {
+		let mut source = std::error::Error::source(self);
+		while let Some(e) = source {
+			let cause = e.to_string();
+			if !message.contains(&cause) {
+				message = format!("{}: {}", message, cause);
+			}
+			source = e.source();
+		}
+
+		message
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::IError;
+
+	#[test]
+	fn test_chain() {
+		let e = IError::AnyError(
+			anyhow::anyhow!("connection refused").context("Fetch databases of `local`"),
+		);
+		assert_eq!(e.chain(), "Fetch databases of `local`: connection refused");
+
+		let e = IError::from(std::io::Error::new(std::io::ErrorKind::Other, "disk full"));
+		assert_eq!(e.chain(), "IO error: disk full");
+		assert_eq!(e.localized(), e.chain());
+	}
+}
@@ -0,0 +1,181 @@
+use std::fmt::{Display, Formatter};
+
+use iced::{
+	theme,
+	widget::{button, container, pick_list, row, text, Column, Container, Row},
+	Alignment, Length, Renderer,
+};
+
+use crate::{
+	error::IResult,
+	i18n::t,
+	mapping::{
+		column::ColumnSpec,
+		database::DbType,
+		diff::{self, ColumnDiff},
+	},
+};
+
+use super::{
+	style::{border_style, button_style, diff_style, DiffStatus},
+	App, Message,
+};
+
+/// The table on another connection which the selected table is compared with.
+#[derive(Debug, Default)]
+pub struct CompareTarget {
+	pub conn: Option<String>,
+	pub databases: Vec<String>,
+	pub database: Option<String>,
+	pub tables: Vec<String>,
+	pub table: Option<String>,
+	pub table_schema: Vec<ColumnSpec>,
+	/// Dialect of the target table, resolved by the client along with its schema.
+	pub dialect: Option<DbType>,
+	/// A request of the target is in flight.
+	pub loading: bool,
+}
+
+/// A connection of the pick list, shown by its name which need not be unique.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConnChoice {
+	uuid: String,
+	name: String,
+}
+
+impl Display for ConnChoice {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.name)
+	}
+}
+
+struct CompareRow<'a> {
+	source: Option<&'a ColumnSpec>,
+	target: Option<&'a ColumnSpec>,
+	status: DiffStatus,
+}
+
+pub fn view(app: &App) -> Container<'_, Message, Renderer> {
+	let tab = app.tab();
+	let target = &tab.compare;
+	let conns = app
+		.all_conns
+		.iter()
+		.map(|conf| ConnChoice { uuid: conf.uuid.clone(), name: conf.name.clone() })
+		.collect::<Vec<_>>();
+	let selected = conns.iter().find(|conn| Some(&conn.uuid) == target.conn.as_ref()).cloned();
+
+	let selector = row![
+		pick_list(conns, selected, |conn| Message::SelectCompareConnection(conn.uuid))
+			.placeholder(t!("compare.target_conn")),
+		pick_list(
+			target.databases.clone(),
+			target.database.clone(),
+			Message::SelectCompareDatabase
+		)
+		.placeholder(t!("compare.target_database")),
+		pick_list(target.tables.clone(), target.table.clone(), Message::SelectCompareTable)
+			.placeholder(t!("compare.target_table")),
+		button(text(t!("common.close")))
+			.style(theme::Button::Secondary)
+			.on_press(Message::ToggleCompare),
+		text(if target.loading { t!("common.loading") } else { String::new() }),
+	]
+	.spacing(5)
+	.align_items(Alignment::Center);
+
+	let source_type = app.source_type();
+	let target_type = target.dialect.unwrap_or(DbType::Internal);
+	let header = Row::new()
+		.push(
+			text(format!("{} ({})", tab.selected_table.as_deref().unwrap_or(""), source_type))
+				.width(Length::Fill),
+		)
+		.push(
+			text(format!("{} ({})", target.table.as_deref().unwrap_or(""), target_type))
+				.width(Length::Fill),
+		)
+		.spacing(10);
+
+	let rows = diff_rows(&tab.origin_table_schema, source_type, &target.table_schema, target_type);
+
+	container(Column::new().push(selector).push(header).push(rows).spacing(10))
+		.width(Length::Fill)
+		.padding(5)
+		.style(border_style())
+}
+
+pub fn toggle_button(app: &App) -> iced::widget::Button<'_, Message, Renderer> {
+	button(text(t!("compare.toggle")))
+		.height(30.0)
+		.style(button_style(app.tab().show_compare))
+		.on_press(Message::ToggleCompare)
+}
+
+/// Columns of both sides aligned by name and coloured by their difference in `target_type`.
+pub fn diff_rows<'a>(
+	source: &'a [ColumnSpec],
+	source_type: DbType,
+	target: &'a [ColumnSpec],
+	target_type: DbType,
+) -> Column<'a, Message, Renderer> {
+	let compare_rows = match compare_rows(source, target, target_type) {
+		Ok(compare_rows) => compare_rows,
+		Err(e) => return Column::new().push(text(e.localized())),
+	};
+	compare_rows.into_iter().fold(Column::new().spacing(2), |base, compare_row| {
+		base.push(
+			container(
+				Row::new()
+					.push(column_cell(compare_row.source, source_type))
+					.push(column_cell(compare_row.target, target_type))
+					.spacing(10),
+			)
+			.width(Length::Fill)
+			.padding(3)
+			.style(diff_style(compare_row.status)),
+		)
+	})
+}
+
+fn column_cell(column: Option<&ColumnSpec>, db_type: DbType) -> Row<'_, Message, Renderer> {
+	match column {
+		Some(column) => Row::new()
+			.push(text(&column.name).width(Length::FillPortion(1)))
+			.push(text(column.r#type.to_type(db_type)).width(Length::FillPortion(1))),
+		None => Row::new().push(text("-")),
+	}
+	.width(Length::Fill)
+}
+
+/// Align columns of both sides by name, source order first and then target-only columns.
+fn compare_rows<'a>(
+	source: &'a [ColumnSpec],
+	target: &'a [ColumnSpec],
+	dialect: DbType,
+) -> IResult<Vec<CompareRow<'a>>> {
+	let diffs = diff::diff_columns(source, target, dialect)?;
+	let status_of = |name: &str| {
+		diffs.iter().find(|diff| diff.name() == name).map_or(DiffStatus::Same, |diff| match diff {
+			ColumnDiff::Added(_) => DiffStatus::Added,
+			ColumnDiff::Removed(_) => DiffStatus::Missing,
+			ColumnDiff::Modified { .. } => DiffStatus::Mismatch,
+		})
+	};
+
+	let mut rows = source
+		.iter()
+		.map(|column| CompareRow {
+			source: Some(column),
+			target: target.iter().find(|target| target.name == column.name),
+			status: status_of(&column.name),
+		})
+		.collect::<Vec<_>>();
+	rows.extend(
+		target.iter().filter(|column| !source.iter().any(|source| source.name == column.name)).map(
+			|column| CompareRow { source: None, target: Some(column), status: DiffStatus::Missing },
+		),
+	);
+
+	Ok(rows)
+}
@@ -0,0 +1,97 @@
+use iced::{
+	theme,
+	widget::{button, column, container, row, text, text_input, Container},
+	Alignment, Application, Length, Renderer,
+};
+
+use crate::i18n::t;
+
+use super::{App, Message};
+
+/// An action waiting for the user to confirm it, shown above everything else.
+#[derive(Debug)]
+pub struct Confirm {
+	pub title: String,
+	pub description: String,
+	pub danger: bool,
+	/// Text which has to be typed before confirming, e.g. the name of a production connection.
+	pub expected_input: Option<String>,
+	pub input: String,
+	/// Dispatched once confirmed.
+	pub on_confirm: Message,
+}
+
+impl Confirm {
+	pub fn new(
+		title: impl Into<String>,
+		description: impl Into<String>,
+		on_confirm: Message,
+	) -> Self {
+		Confirm {
+			title: title.into(),
+			description: description.into(),
+			danger: false,
+			expected_input: None,
+			input: String::new(),
+			on_confirm,
+		}
+	}
+
+	pub fn danger(self) -> Self {
+		Confirm { danger: true, ..self }
+	}
+
+	/// Require `expected` to be typed when `required` holds.
+	pub fn typed(self, required: bool, expected: &str) -> Self {
+		Confirm { expected_input: required.then(|| expected.to_owned()), ..self }
+	}
+
+	pub fn is_satisfied(&self) -> bool {
+		self.expected_input.as_ref().map_or(true, |expected| expected == &self.input)
+	}
+}
+
+pub fn view<'a>(app: &App, confirm: &'a Confirm) -> Container<'a, Message, Renderer> {
+	let title = text(&confirm.title).size(20);
+	let title = if confirm.danger {
+		title.style(theme::Text::Color(app.theme().extended_palette().danger.base.color))
+	} else {
+		title
+	};
+
+	let mut content = column![title, text(&confirm.description)].spacing(10);
+	if let Some(expected) = &confirm.expected_input {
+		content = content.push(text(t!("confirm.type_to_confirm", expected = expected))).push(
+			text_input(expected, &confirm.input, Message::EditConfirmInput).on_submit(
+				if confirm.is_satisfied() { Message::SubmitConfirm } else { Message::Nothing },
+			),
+		);
+	}
+
+	let confirm_button = button(text(t!("common.confirm"))).style(if confirm.danger {
+		theme::Button::Destructive
+	} else {
+		theme::Button::Primary
+	});
+	let confirm_button = if confirm.is_satisfied() {
+		confirm_button.on_press(Message::SubmitConfirm)
+	} else {
+		confirm_button
+	};
+
+	container(
+		content.push(
+			row![
+				confirm_button,
+				button(text(t!("common.cancel")))
+					.style(theme::Button::Secondary)
+					.on_press(Message::CancelConfirm)
+			]
+			.spacing(5)
+			.align_items(Alignment::End),
+		),
+	)
+	.padding(20)
+	.width(Length::Fixed(420.0))
+	.style(theme::Container::Box)
+}
@@ -2,34 +2,51 @@ use iced::{
 	alignment::Vertical,
 	theme,
 	widget::{
-		button, column, container, pick_list, row, scrollable, text, text_input, Column, Container,
-		Row,
+		button, checkbox, column, container, pick_list, row, scrollable, text, text_input, Column,
+		Container, Row,
 	},
-	Length, Renderer,
+	Application, Element, Length, Renderer,
 };
 
 use super::{
-	style::{border_style, button_style},
-	App, Message,
+	compare, ddl_preview, history, logs, snapshots,
+	style::{border_style, button_style, muted_color},
+	tab, App, Message,
+};
+use crate::{
+	conn::endpoint::{SslMode, CH_COMPRESSIONS},
+	gui::modal::Modal,
+	i18n::t,
+	mapping::database::DbType,
 };
-use crate::{gui::modal::Modal, mapping::database::DbType};
 
 pub fn view(app: &App) -> Container<Message, Renderer> {
-	let databases = scrollable(show_databases(app))
-		.horizontal_scroll(scrollable::Properties::new().width(1.0).scroller_width(2.0));
-	let tables = scrollable(show_tables(app))
-		.horizontal_scroll(scrollable::Properties::new().width(1.0).scroller_width(2.0));
-	let content = Column::new()
-		.push(databases)
-		.push(tables)
-		.push(show_db_types(app))
-		.push(show_table_schema(app));
-
-	let mut content_wrapper = container(scrollable(content))
-		.width(Length::FillPortion(4))
-		.height(Length::Fill)
-		.padding(5)
-		.style(border_style());
+	let mut content_wrapper = if app.show_history {
+		history::view(app)
+	} else if app.show_logs {
+		logs::view(app)
+	} else if app.show_snapshots {
+		snapshots::view(app)
+	} else {
+		let tab = app.tab();
+		let content =
+			Column::new().push(tab::view(app)).push(show_selected(app)).push(show_db_types(app));
+		let content = if tab.show_compare && tab.selected_table.is_some() {
+			content.push(compare::view(app))
+		} else {
+			content.push(show_table_schema(app))
+		};
+		let content = if tab.selected_db_type.is_some() && !tab.origin_table_schema.is_empty() {
+			content.push(ddl_preview::view(app))
+		} else {
+			content
+		};
+		container(scrollable(content))
+	}
+	.width(Length::FillPortion(4))
+	.height(Length::Fill)
+	.padding(5)
+	.style(border_style());
 
 	if app.show_conn_modal {
 		content_wrapper = container(
@@ -43,12 +60,34 @@ pub fn view(app: &App) -> Container<Message, Renderer> {
 	content_wrapper
 }
 
+fn show_selected(app: &App) -> Row<'_, Message, Renderer> {
+	let tab = app.tab();
+	let conn = app
+		.all_conns
+		.iter()
+		.find(|conf| Some(&conf.uuid) == tab.selected_conn.as_ref())
+		.map(|conf| conf.name.as_str());
+	let path = match (conn, tab.selected_db.as_deref(), tab.selected_table.as_deref()) {
+		(Some(conn), Some(database), Some(table)) => format!("{} / {} / {}", conn, database, table),
+		(None, _, Some(_)) if tab.pasted.is_some() => {
+			format!("{} / {}", t!("content.pasted", db_type = app.source_type()), tab.title())
+		}
+		_ => t!("content.select_table"),
+	};
+
+	Row::new().push(text(path).size(20)).padding(5)
+}
+
 fn show_table_schema(app: &App) -> Column<'_, Message, Renderer> {
-	let Some(db_type) = app.selected_db_type else {
+	let tab = app.tab();
+	let Some(db_type) = tab.selected_db_type else {
 		return Column::new();
 	};
+	if tab.loading_schema {
+		return Column::new().push(text(t!("common.loading")));
+	}
 
-	app.origin_table_schema
+	tab.origin_table_schema
 		.iter()
 		.fold(Column::new(), |base, column_spec| {
 			base.push(
@@ -63,120 +102,165 @@ fn show_table_schema(app: &App) -> Column<'_, Message, Renderer> {
 }
 
 fn show_db_types(app: &App) -> Row<'_, Message, Renderer> {
-	if app.selected_table.is_none() {
+	if app.tab().selected_table.is_none() {
 		Row::new()
 	} else {
-		DbType::ALL.iter().fold(Row::new(), |base, db_type| {
-			base.push(
-				button(text(db_type))
+		let db_types = DbType::ALL
+			.iter()
+			.fold(Row::new(), |base, db_type| {
+				base.push(
+					button(text(db_type))
+						.height(30.0)
+						.style(button_style(
+							matches!(&app.tab().selected_db_type, Some(selected) if selected == db_type),
+						))
+						.on_press(Message::SelectDBType(db_type.clone())),
+				)
+			})
+			.push(compare::toggle_button(app));
+
+		let db_types =
+			if app.tab().selected_conn.is_none() || app.tab().origin_table_schema.is_empty() {
+				db_types
+			} else {
+				db_types.push(
+					button(text(t!("content.take_snapshot")))
+						.height(30.0)
+						.style(theme::Button::Secondary)
+						.on_press(Message::TakeSnapshot),
+				)
+			};
+
+		#[cfg(feature = "json")]
+		let db_types = if app.tab().origin_table_schema.is_empty() {
+			db_types
+		} else {
+			db_types.push(
+				button(text(t!("content.copy_json")))
 					.height(30.0)
-					.style(button_style(
-						matches!(&app.selected_db_type, Some(selected) if selected == db_type),
-					))
-					.on_press(Message::SelectDBType(db_type.clone())),
+					.style(theme::Button::Secondary)
+					.on_press(Message::CopyTableAsJson),
 			)
-		})
+		};
+
+		db_types
 	}
 	.height(40.0)
 	.padding(5)
 	.spacing(5)
 }
 
-fn show_tables(app: &App) -> Row<'_, Message, Renderer> {
-	if app.tables.is_empty() {
-		Row::new()
-	} else {
-		app.tables.iter().fold(Row::new(), |base, table| {
-			base.push(
-				button(text(table))
-					.height(30.0)
-					.style(button_style(
-						matches!(&app.selected_table, Some(selected) if selected == table),
-					))
-					.on_press(Message::SelectTable(table.clone())),
-			)
-		})
+fn edit_conn_form<'a>(app: &App) -> Container<'a, Message, Renderer> {
+	let endpoint = &app.edit_endpoint;
+	let mut fields = column![form_row(
+		t!("conn.type"),
+		pick_list(&DbType::CONNECTABLE[..], app.edit_conn.db_type, Message::EditConnDbType)
+			.width(Length::Fill)
+			.placeholder(t!("conn.choose_type"))
+	)]
+	.spacing(10);
+
+	match app.edit_conn.db_type {
+		Some(db_type @ (DbType::MySQL | DbType::ClickHouse)) => {
+			let port = endpoint.port.map(|port| port.to_string()).unwrap_or_default();
+			fields = fields
+				.push(form_row(
+					t!("conn.host"),
+					text_input("host", &endpoint.host, Message::EditConnHost),
+				))
+				.push(form_row(t!("conn.port"), text_input("port", &port, Message::EditConnPort)))
+				.push(form_row(
+					t!("conn.database"),
+					text_input("database", &endpoint.database, Message::EditConnDatabase),
+				));
+			fields = if db_type == DbType::ClickHouse {
+				let compression =
+					CH_COMPRESSIONS.into_iter().find(|c| Some(*c) == endpoint.param("compression"));
+				fields.push(form_row(
+					t!("conn.compression"),
+					pick_list(&CH_COMPRESSIONS[..], compression, |compression| {
+						Message::EditConnCompression(compression.to_owned())
+					})
+					.width(Length::Fill)
+					.placeholder("Lz4"),
+				))
+			} else {
+				fields.push(form_row(
+					t!("conn.ssl_mode"),
+					pick_list(
+						&SslMode::ALL[..],
+						Some(endpoint.ssl_mode()),
+						Message::EditConnSslMode,
+					)
+					.width(Length::Fill),
+				))
+			};
+			fields = fields.push(form_row(
+				t!("conn.url"),
+				text(app.edit_conn.url.clone())
+					.style(theme::Text::Color(muted_color(&app.theme()))),
+			));
+		}
+		Some(DbType::Fixture) => {
+			fields = fields.push(form_row(
+				t!("conn.fixture_file"),
+				text_input("shop.toml", &app.edit_conn.url, Message::EditConnUrl),
+			));
+		}
+		Some(DbType::Directory) => {
+			fields = fields.push(form_row(
+				t!("conn.schema_dir"),
+				text_input("schemas", &app.edit_conn.url, Message::EditConnUrl),
+			));
+		}
+		_ => {
+			fields = fields.push(form_row(
+				t!("conn.url"),
+				text_input("url", &app.edit_conn.url, Message::EditConnUrl),
+			));
+		}
 	}
-	.height(Length::Fixed(40.0))
-	.padding(5)
-	.spacing(5)
-}
 
-fn show_databases(app: &App) -> Row<'_, Message, Renderer> {
-	if app.databases.is_empty() {
-		Row::new().push("nothing")
+	let test_button =
+		button(text(if app.testing_conn { t!("conn.testing") } else { t!("conn.test") }))
+			.style(theme::Button::Secondary);
+	let test_button = if app.testing_conn || app.edit_conn.db_type.is_none() {
+		test_button
 	} else {
-		app.databases.iter().fold(Row::new(), |base, database| {
-			base.push(
-				button(text(database))
-					.height(30.0)
-					.style(button_style(
-						matches!(&app.selected_db, Some(selected) if selected == database),
-					))
-					.on_press(Message::SelectDatabase(database.clone())),
-			)
-		})
-	}
-	.height(Length::Fixed(40.0))
-	.padding(5)
-	.spacing(5)
-}
+		test_button.on_press(Message::TestConnection)
+	};
 
-fn edit_conn_form<'a>(app: &App) -> Container<'a, Message, Renderer> {
 	container(
 		column![
-			text("New Connection").size(20),
-			row![
-				text("Name")
-					.size(16)
-					.width(Length::Fixed(80.0))
-					.vertical_alignment(Vertical::Center),
+			text(t!("conn.title")).size(20),
+			form_row(
+				t!("conn.name"),
 				text_input("name", &app.edit_conn.name, Message::EditConnName)
-			]
-			.spacing(5),
-			column![
-				row![
-					text("Type")
-						.size(16)
-						.width(Length::Fixed(80.0))
-						.vertical_alignment(Vertical::Center),
-					pick_list(&DbType::ALL[..], app.edit_conn.db_type, Message::EditConnDbType,)
-						.width(Length::Fill)
-						.placeholder("Choose database type...")
-				]
-				.spacing(5),
-				row![
-					text("Url")
-						.size(16)
-						.width(Length::Fixed(80.0))
-						.vertical_alignment(Vertical::Center),
-					text_input("url", &app.edit_conn.url, Message::EditConnUrl)
-				]
-				.spacing(5),
-				row![
-					text("Username")
-						.size(16)
-						.width(Length::Fixed(80.0))
-						.vertical_alignment(Vertical::Center),
+			),
+			fields
+				.push(form_row(
+					t!("conn.username"),
 					text_input("username", &app.edit_conn.username, Message::EditConnUsername)
-				]
-				.spacing(5),
-				row![
-					text("Password")
-						.size(16)
-						.width(Length::Fixed(80.0))
-						.vertical_alignment(Vertical::Center),
+				))
+				.push(form_row(
+					t!("conn.password"),
 					text_input("password", &app.edit_conn.password, Message::EditConnPassword)
-				]
-				.spacing(5),
-				row![
-					button(text("Submit")).on_press(Message::SubmitConnForm),
-					button(text("Close")).on_press(Message::CloseConnForm),
-				]
-				.align_items(iced::Alignment::End)
-				.spacing(5)
-			]
-			.spacing(10)
+						.password()
+				))
+				.push(checkbox(
+					t!("conn.production"),
+					app.edit_conn.production,
+					Message::EditConnProduction
+				))
+				.push(
+					row![
+						button(text(t!("common.submit"))).on_press(Message::SubmitConnForm),
+						test_button,
+						button(text(t!("common.close"))).on_press(Message::CloseConnForm),
+					]
+					.align_items(iced::Alignment::End)
+					.spacing(5)
+				)
 		]
 		.spacing(20),
 	)
@@ -184,3 +268,14 @@ fn edit_conn_form<'a>(app: &App) -> Container<'a, Message, Renderer> {
 	.width(Length::Fixed(400.0))
 	.style(theme::Container::Box)
 }
+
+fn form_row<'a>(
+	label: String,
+	input: impl Into<Element<'a, Message, Renderer>>,
+) -> Row<'a, Message, Renderer> {
+	row![
+		text(label).size(16).width(Length::Fixed(80.0)).vertical_alignment(Vertical::Center),
+		input.into()
+	]
+	.spacing(5)
+}
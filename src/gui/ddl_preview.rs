@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use iced::{
+	theme::{self, palette::Extended},
+	widget::{button, container, row, scrollable, text, Column, Container, Row},
+	Application, Length, Renderer,
+};
+
+use crate::{
+	error::{IError, IResult},
+	i18n::t,
+	mapping::{database::DbType, diff, table::Table},
+	util,
+};
+
+use super::{style::border_style, App, Message};
+
+const KEYWORDS: &[&'static str] = &[
+	"ADD",
+	"AFTER",
+	"ALTER",
+	"AUTO_INCREMENT",
+	"BY",
+	"COLUMN",
+	"COMMENT",
+	"CREATE",
+	"DEFAULT",
+	"DROP",
+	"ENGINE",
+	"EXISTS",
+	"IF",
+	"KEY",
+	"MODIFY",
+	"NOT",
+	"NULL",
+	"ORDER",
+	"PARTITION",
+	"PRIMARY",
+	"TABLE",
+	"UNSIGNED",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+	Keyword,
+	/// Single quoted string literal.
+	Literal,
+	/// Backtick quoted identifier.
+	Identifier,
+	Plain,
+}
+
+/// The ALTER plan against the compared table when there is one, CREATE TABLE otherwise.
+pub fn statements(app: &App) -> IResult<Vec<String>> {
+	let source = app.source_table();
+	match compared_table(app) {
+		Some(target) => Ok(diff::plan(&source, &target, false)?.1),
+		None => {
+			let db_type =
+				app.tab().selected_db_type.ok_or(IError::PromptError(t!("ddl.no_db_type")))?;
+			Ok(vec![source.to_create_sql(db_type)?])
+		}
+	}
+}
+
+pub fn generate(app: &App) -> IResult<String> {
+	let statements = statements(app)?;
+	if statements.is_empty() {
+		return Ok(format!("-- {}", t!("ddl.nothing_to_change")));
+	}
+
+	Ok(statements.iter().map(|sql| format!("{};", sql)).collect::<Vec<_>>().join("\n\n"))
+}
+
+/// Where the preview is saved, e.g. `<app dir>/ddl/db.user.clickhouse.sql`, numbered as
+/// `db.user.clickhouse.2.sql` and so on rather than overwriting an earlier file.
+pub fn file_path(app: &App) -> PathBuf {
+	let tab = app.tab();
+	let dialect = match compared_table(app) {
+		Some(target) => format!("alter.{}", target.r#type),
+		None => tab.selected_db_type.unwrap_or_default().to_string(),
+	};
+	let stem = format!(
+		"{}.{}.{}",
+		tab.selected_db.as_deref().unwrap_or_default(),
+		tab.selected_table.as_deref().unwrap_or_default(),
+		dialect.to_lowercase()
+	);
+	(1..)
+		.map(|n| match n {
+			1 => util::app_ddl_dir().join(format!("{}.sql", stem)),
+			n => util::app_ddl_dir().join(format!("{}.{}.sql", stem, n)),
+		})
+		.find(|path| !app.backend.store.file_exists(path))
+		.unwrap_or_default()
+}
+
+pub fn view(app: &App) -> Container<'_, Message, Renderer> {
+	let sql = generate(app).unwrap_or_else(|e| format!("-- {}", e.localized()));
+	let app_theme = app.theme();
+	let palette = app_theme.extended_palette();
+	let lines = sql
+		.lines()
+		.fold(Column::new(), |base, line| base.push(highlight(line, palette)))
+		.width(Length::Fill);
+
+	let actions = row![
+		button(text(t!("common.copy")))
+			.style(theme::Button::Secondary)
+			.on_press(Message::CopyToClipboard(sql.clone())),
+		button(text(t!("ddl.save"))).style(theme::Button::Secondary).on_press(Message::SaveDdl),
+	]
+	.spacing(5);
+	let actions = if compared_table(app).is_some() && !sql.starts_with("--") {
+		actions.push(
+			button(text(t!("ddl.apply")))
+				.style(theme::Button::Destructive)
+				.on_press(Message::ApplyPlan),
+		)
+	} else {
+		actions
+	};
+
+	container(
+		Column::new()
+			.push(actions)
+			.push(
+				scrollable(lines).height(Length::Fixed(240.0)).horizontal_scroll(
+					scrollable::Properties::new().width(1.0).scroller_width(2.0),
+				),
+			)
+			.spacing(5),
+	)
+	.width(Length::Fill)
+	.padding(5)
+	.style(border_style())
+}
+
+pub fn compared_table(app: &App) -> Option<Table> {
+	let tab = app.tab();
+	if !tab.show_compare || tab.compare.table_schema.is_empty() {
+		return None;
+	}
+
+	Some(Table::new(
+		tab.compare.database.clone().unwrap_or_default(),
+		tab.compare.table.clone().unwrap_or_default(),
+		tab.compare.dialect.unwrap_or(DbType::Internal),
+		tab.compare.table_schema.clone(),
+	))
+}
+
+fn highlight<'a>(line: &str, palette: &Extended) -> Row<'a, Message, Renderer> {
+	tokenize(line).into_iter().fold(Row::new(), |base, (token, part)| {
+		let part = text(part.to_owned()).size(16);
+		base.push(match token {
+			Token::Keyword => part.style(theme::Text::Color(palette.primary.base.color)),
+			Token::Literal => part.style(theme::Text::Color(palette.success.base.color)),
+			Token::Identifier => part.style(theme::Text::Color(palette.secondary.strong.color)),
+			Token::Plain => part,
+		})
+	})
+}
+
+fn tokenize(line: &str) -> Vec<(Token, &str)> {
+	let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+	let mut tokens: Vec<(Token, &str)> = Vec::new();
+	let mut pos = 0;
+	while let Some(c) = line[pos..].chars().next() {
+		let rest = &line[pos..];
+		let (token, len) = match c {
+			'\'' | '`' => {
+				let len = rest[1..].find(c).map_or(rest.len(), |end| end + 2);
+				(if c == '\'' { Token::Literal } else { Token::Identifier }, len)
+			}
+			c if is_word(c) => {
+				let len = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+				(if KEYWORDS.contains(&&rest[..len]) { Token::Keyword } else { Token::Plain }, len)
+			}
+			_ => {
+				let len = rest.find(|c| is_word(c) || c == '\'' || c == '`').unwrap_or(rest.len());
+				(Token::Plain, len)
+			}
+		};
+
+		// Merge adjacent plain parts so a line renders with as few widgets as possible.
+		match tokens.last_mut() {
+			Some((Token::Plain, plain)) if token == Token::Plain => {
+				*plain = &line[pos - plain.len()..pos + len]
+			}
+			_ => tokens.push((token, &rest[..len])),
+		}
+		pos += len;
+	}
+
+	tokens
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{tokenize, Token};
+
+	#[test]
+	fn test_tokenize() {
+		assert_eq!(
+			tokenize("  `name` Nullable(String) COMMENT 'user''s name',"),
+			vec![
+				(Token::Plain, "  "),
+				(Token::Identifier, "`name`"),
+				(Token::Plain, " Nullable(String) "),
+				(Token::Keyword, "COMMENT"),
+				(Token::Plain, " "),
+				(Token::Literal, "'user'"),
+				(Token::Literal, "'s name'"),
+				(Token::Plain, ","),
+			]
+		);
+		assert_eq!(
+			tokenize("ALTER TABLE `db`.`t` DROP COLUMN `c`")[..3],
+			[(Token::Keyword, "ALTER"), (Token::Plain, " "), (Token::Keyword, "TABLE")]
+		);
+		assert_eq!(tokenize("'unclosed"), vec![(Token::Literal, "'unclosed")]);
+	}
+}
@@ -1,7 +1,71 @@
-use iced::Command;
+use iced::{
+	keyboard::{self, KeyCode},
+	Command,
+};
 
-use super::Message;
+use crate::mapping::database::DbType;
 
-pub fn handle(_: iced::Event) -> Command<Message> {
-	Command::none()
+use super::{App, Message};
+
+/// `Ctrl+1`.. switch the target `DbType`, in the order of `DbType::ALL`.
+const DB_TYPE_KEYS: [KeyCode; 4] = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+
+/// Keyboard shortcuts:
+/// - `Ctrl+K` command palette, `Up`/`Down` move its selection, `Esc` closes it or the dialogs
+/// - `Ctrl+N` new connection, `Ctrl+R` refresh, `Ctrl+F` search tables
+/// - `Ctrl+T` new tab, `Ctrl+W` close it, `Ctrl+Tab`/`Ctrl+Shift+Tab` switch tabs
+/// - `Ctrl+1`..`Ctrl+4` switch the target `DbType`, `Ctrl+Shift+C` copy DDL
+pub fn handle(app: &mut App, event: iced::Event) -> Command<Message> {
+	match shortcut(app, event) {
+		Some(message) => app.update(message),
+		None => Command::none(),
+	}
+}
+
+pub fn shortcut(app: &App, event: iced::Event) -> Option<Message> {
+	let (key_code, modifiers) = match event {
+		iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }) => {
+			(key_code, modifiers)
+		}
+		_ => return None,
+	};
+
+	if app.confirm.is_some() {
+		return (key_code == KeyCode::Escape).then_some(Message::CancelConfirm);
+	}
+
+	if app.palette.is_some() {
+		return match key_code {
+			KeyCode::Escape => Some(Message::ClosePalette),
+			KeyCode::K if modifiers.command() => Some(Message::ClosePalette),
+			KeyCode::Up => Some(Message::MovePaletteSelection(-1)),
+			KeyCode::Down => Some(Message::MovePaletteSelection(1)),
+			_ => None,
+		};
+	}
+
+	if key_code == KeyCode::Escape {
+		return app.show_conn_modal.then_some(Message::CloseConnForm);
+	}
+
+	if !modifiers.command() {
+		return None;
+	}
+
+	match key_code {
+		KeyCode::K => Some(Message::OpenPalette),
+		KeyCode::N => Some(Message::EditConnection(None)),
+		KeyCode::R => Some(Message::Refresh),
+		KeyCode::F => Some(Message::FocusSearch),
+		KeyCode::T => Some(Message::NewTab),
+		KeyCode::W => Some(Message::CloseTab(app.workspace.active)),
+		KeyCode::Tab => Some(Message::CycleTab(if modifiers.shift() { -1 } else { 1 })),
+		KeyCode::C if modifiers.shift() => Some(Message::CopyDdl),
+		_ if app.tab().selected_table.is_some() => DB_TYPE_KEYS
+			.iter()
+			.position(|key| *key == key_code)
+			.and_then(|index| DbType::ALL.get(index))
+			.map(|db_type| Message::SelectDBType(*db_type)),
+		_ => None,
+	}
 }
@@ -1,15 +1,35 @@
 use iced::{
-	widget::{button, container, Row},
+	widget::{button, container, pick_list, text, Row},
 	Length, Renderer,
 };
 
+use crate::{
+	conf::{Language, ThemeKind},
+	i18n::t,
+	mapping::database::DbType,
+};
+
 use super::{style::border_style, App, Message};
 
-pub fn view<'a>(_: &App) -> iced::widget::Container<'a, Message, Renderer> {
+pub fn view<'a>(app: &App) -> iced::widget::Container<'a, Message, Renderer> {
+	let settings = app.backend.store.settings();
+	let theme_label = match settings.theme {
+		ThemeKind::Light => t!("header.dark_mode"),
+		ThemeKind::Dark => t!("header.light_mode"),
+	};
+
 	container(
 		Row::new()
-			.push(button("Create").on_press(Message::EditConnection(None)))
-			.push(button("blank").on_press(Message::Nothing))
+			.push(button(text(t!("header.create"))).on_press(Message::EditConnection(None)))
+			.push(button(text(t!("header.history"))).on_press(Message::ToggleHistory))
+			.push(button(text(t!("header.snapshots"))).on_press(Message::ToggleSnapshots))
+			.push(button(text(t!("header.logs"))).on_press(Message::ToggleLogs))
+			.push(
+				pick_list(&DbType::PARSABLE[..], None, Message::PasteDdl)
+					.placeholder(t!("header.paste_ddl")),
+			)
+			.push(button(text(theme_label)).on_press(Message::ToggleTheme))
+			.push(pick_list(Language::ALL, Some(settings.language), Message::SelectLanguage))
 			.spacing(10)
 			.padding(10),
 	)
@@ -0,0 +1,85 @@
+use iced::{
+	theme,
+	widget::{
+		button, checkbox, container, row, scrollable, text, text_input, Column, Container, Row,
+	},
+	Alignment, Length, Renderer,
+};
+
+use crate::i18n::t;
+
+use super::{style::border_style, App, Message};
+
+pub fn view(app: &App) -> Container<'_, Message, Renderer> {
+	let filter = row![
+		text_input(&t!("history.filter"), &app.history_filter.keyword, Message::EditHistoryKeyword),
+		checkbox(
+			t!("history.current_conn"),
+			app.history_filter.conn_uuid.is_some(),
+			Message::ToggleHistoryConnOnly
+		),
+		checkbox(
+			t!("history.failed_only"),
+			app.history_filter.failed_only,
+			Message::ToggleHistoryFailedOnly
+		),
+	]
+	.spacing(10)
+	.align_items(Alignment::Center);
+
+	let histories = if app.histories.is_empty() {
+		Column::new().push(text(t!("history.empty")))
+	} else {
+		app.histories.iter().fold(Column::new(), |base, history| {
+			base.push(
+				container(
+					Row::new()
+						.push(
+							Column::new()
+								.push(
+									text(format!(
+										"{} {} {}.{} {}ms {}",
+										history.executed_at,
+										history.executor,
+										history.database,
+										history.table,
+										history.duration_ms,
+										if history.success {
+											t!("history.ok")
+										} else {
+											t!("history.failed")
+										}
+									))
+									.size(14),
+								)
+								.push(text(&history.statement))
+								.push(text(&history.message).size(14))
+								.width(Length::Fill)
+								.spacing(5),
+						)
+						.push(
+							button(text(t!("common.copy")))
+								.style(theme::Button::Secondary)
+								.on_press(Message::CopyToClipboard(history.statement.clone())),
+						)
+						.push(
+							button(text(t!("history.rerun")))
+								.style(theme::Button::Destructive)
+								.on_press(Message::RerunDdl(history.id)),
+						)
+						.align_items(Alignment::Center)
+						.spacing(5),
+				)
+				.width(Length::Fill)
+				.padding(5)
+				.style(border_style()),
+			)
+		})
+	}
+	.spacing(5)
+	.width(Length::Fill);
+
+	container(Column::new().push(filter).push(scrollable(histories)).spacing(10))
+		.width(Length::Fill)
+		.height(Length::Fill)
+}
@@ -0,0 +1,178 @@
+use iced::{
+	theme,
+	widget::{button, column, container, pick_list, row, scrollable, text, Column, Container},
+	Alignment, Application, Length, Renderer,
+};
+use tracing::Level;
+
+use crate::{conf, conn::query_log::QueryRecord, error::IResult, i18n::t, util};
+
+use super::{
+	style::{border_style, button_style, muted_color},
+	App, Message,
+};
+
+/// Levels offered by the filter, most severe first.
+pub const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+/// Lines read from the end of the log file.
+const MAX_LINES: usize = 1000;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LogTab {
+	#[default]
+	File,
+	Queries,
+}
+
+#[derive(Debug)]
+pub struct LogView {
+	pub tab: LogTab,
+	/// Lines less severe than this are hidden.
+	pub level: Level,
+	pub lines: Vec<(Level, String)>,
+	/// Copy of the query log taken when the panel was opened or reloaded, newest first.
+	pub queries: Vec<QueryRecord>,
+}
+
+impl Default for LogView {
+	fn default() -> Self {
+		LogView {
+			tab: LogTab::default(),
+			level: Level::INFO,
+			lines: Vec::new(),
+			queries: Vec::new(),
+		}
+	}
+}
+
+/// Tail of the current log file with the level of each line.
+pub fn load() -> IResult<Vec<(Level, String)>> {
+	match conf::current_log_file() {
+		Some(path) => Ok(with_levels(util::tail_lines(&path, MAX_LINES)?)),
+		None => Ok(Vec::new()),
+	}
+}
+
+/// Lines without a level, e.g. continuations of a multi-line message, take the previous one.
+fn with_levels(lines: Vec<String>) -> Vec<(Level, String)> {
+	let mut level = Level::INFO;
+	lines
+		.into_iter()
+		.map(|line| {
+			level = line
+				.split_whitespace()
+				.take(4)
+				.find_map(|token| match token {
+					"ERROR" => Some(Level::ERROR),
+					"WARN" => Some(Level::WARN),
+					"INFO" => Some(Level::INFO),
+					"DEBUG" => Some(Level::DEBUG),
+					"TRACE" => Some(Level::TRACE),
+					_ => None,
+				})
+				.unwrap_or(level);
+			(level, line)
+		})
+		.collect()
+}
+
+pub fn view(app: &App) -> Container<'_, Message, Renderer> {
+	let log_view = &app.log_view;
+	let mut toolbar = row![
+		button(text(t!("logs.file")))
+			.style(button_style(log_view.tab == LogTab::File))
+			.on_press(Message::SelectLogTab(LogTab::File)),
+		button(text(t!("logs.queries")))
+			.style(button_style(log_view.tab == LogTab::Queries))
+			.on_press(Message::SelectLogTab(LogTab::Queries)),
+	]
+	.spacing(5)
+	.align_items(Alignment::Center);
+	if log_view.tab == LogTab::File {
+		toolbar =
+			toolbar.push(pick_list(&LEVELS[..], Some(log_view.level), Message::SelectLogLevel));
+	}
+	toolbar = toolbar.push(
+		button(text(t!("logs.reload")))
+			.style(theme::Button::Secondary)
+			.on_press(Message::ReloadLogs),
+	);
+
+	let entries = match log_view.tab {
+		LogTab::File => file_lines(app),
+		LogTab::Queries => queries(app),
+	};
+
+	container(column![toolbar, scrollable(entries.width(Length::Fill))].spacing(10))
+		.width(Length::Fill)
+		.height(Length::Fill)
+}
+
+fn file_lines(app: &App) -> Column<'_, Message, Renderer> {
+	let app_theme = app.theme();
+	let palette = app_theme.extended_palette();
+	let lines = app.log_view.lines.iter().filter(|(level, _)| *level <= app.log_view.level);
+	lines.fold(Column::new(), |base, (level, line)| {
+		let line = text(line).size(14);
+		base.push(match *level {
+			Level::ERROR => line.style(theme::Text::Color(palette.danger.base.color)),
+			Level::WARN => line.style(theme::Text::Color(palette.primary.base.color)),
+			_ => line,
+		})
+	})
+}
+
+fn queries(app: &App) -> Column<'_, Message, Renderer> {
+	let records = &app.log_view.queries;
+	if records.is_empty() {
+		return Column::new().push(text(t!("logs.empty")));
+	}
+
+	let app_theme = app.theme();
+	records.iter().fold(Column::new().spacing(5), |base, record| {
+		let (name, _) = app.conn_label(&record.conn);
+		let outcome = match &record.error {
+			Some(error) => text(error)
+				.size(14)
+				.style(theme::Text::Color(app_theme.extended_palette().danger.base.color)),
+			None => text(t!("logs.rows", rows = record.rows.unwrap_or_default())).size(14),
+		};
+		base.push(
+			container(
+				column![
+					row![
+						text(format!("{} {} {}ms", record.executed_at, name, record.duration_ms))
+							.size(14)
+							.style(theme::Text::Color(muted_color(&app_theme))),
+						outcome
+					]
+					.spacing(10),
+					text(&record.sql)
+				]
+				.spacing(5),
+			)
+			.width(Length::Fill)
+			.padding(5)
+			.style(border_style()),
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use tracing::Level;
+
+	use super::with_levels;
+
+	#[test]
+	fn test_with_levels() {
+		let lines = vec![
+			"2023-02-01 10:00:00  WARN schema_syncr::conn: slow".to_owned(),
+			"  continued".to_owned(),
+			"2023-02-01 10:00:01 ERROR schema_syncr::gui: failed".to_owned(),
+		];
+		let levels = with_levels(lines).into_iter().map(|(level, _)| level).collect::<Vec<_>>();
+		assert_eq!(levels, vec![Level::WARN, Level::WARN, Level::ERROR]);
+	}
+}
@@ -1,51 +1,178 @@
+use std::{path::PathBuf, sync::Arc};
+
 use iced::{
-	widget::{self, Column, Row},
+	widget::{self, text_input, Column, Row},
 	Application, Command, Element, Length, Subscription,
 };
 
 use crate::{
-	conn::DBClient,
+	conf::{Language, Settings, ThemeKind},
+	conn::{
+		endpoint::{Endpoint, SslMode},
+		query_log, ClientFactory, ConnTest, DBClientFactory, DBParam, SchemaClient,
+	},
 	error::{IError, IResult},
-	mapping::{column::ColumnSpec, database::DbType},
-	store::conn_conf::{self, ConnConf},
+	i18n::{self, t},
+	mapping::{column::ColumnSpec, database::DbType, ddl_parser, table::Table},
+	store::{
+		conn_conf::ConnConf,
+		ddl_history::{self, DdlHistory, HistoryFilter},
+		snapshot, SqliteStore, Store,
+	},
 };
 
-use self::toast::Toast;
+use self::{
+	compare::CompareTarget,
+	confirm::Confirm,
+	logs::{LogTab, LogView},
+	modal::Modal,
+	navigator::Navigator,
+	palette::Palette,
+	snapshots::SnapshotView,
+	tab::{Tab, Workspace},
+	toast::Toast,
+};
 
+mod compare;
+mod confirm;
 mod content;
+mod ddl_preview;
 mod event_handler;
 mod header;
+mod history;
+mod logs;
 mod modal;
+mod navigator;
+mod palette;
 mod sidebar;
+mod snapshots;
 mod style;
+mod tab;
+#[cfg(test)]
+mod tests;
 mod toast;
 
 #[derive(Debug, Default)]
 pub struct App {
 	pub show_conn_modal: bool,
 	pub edit_conn: ConnConf,
+	/// `edit_conn.url` split into the fields of the form.
+	pub edit_endpoint: Endpoint,
+	pub testing_conn: bool,
 	pub all_conns: Vec<ConnConf>,
-	pub selected_conn: Option<String>,
-	pub databases: Vec<String>,
-	pub selected_db: Option<String>,
-	pub tables: Vec<String>,
-	pub selected_table: Option<String>,
-	pub selected_db_type: Option<DbType>,
-	pub origin_table_schema: Vec<ColumnSpec>,
+	pub navigator: Navigator,
+	pub workspace: Workspace,
+	pub show_history: bool,
+	pub palette: Option<Palette>,
+	pub confirm: Option<Confirm>,
+	pub history_filter: HistoryFilter,
+	pub histories: Vec<DdlHistory>,
+	pub show_logs: bool,
+	pub log_view: LogView,
+	pub show_snapshots: bool,
+	pub snapshot_view: SnapshotView,
 	pub toasts: Vec<Toast>,
+	pub backend: Backend,
+}
+
+/// Where `App` reads and writes connections, history, snapshots and databases.
+#[derive(Debug, Clone)]
+pub struct Backend {
+	pub store: Arc<dyn Store>,
+	pub clients: Arc<dyn ClientFactory>,
+}
+
+impl Default for Backend {
+	fn default() -> Self {
+		Backend { store: Arc::new(SqliteStore), clients: Arc::new(DBClientFactory) }
+	}
+}
+
+impl Backend {
+	fn client(&self, conn_uuid: &String) -> IResult<Arc<dyn SchemaClient>> {
+		self.clients.client(self.store.query_conn(conn_uuid)?.try_into()?)
+	}
+
+	/// Execute `statements` on a table in order, each execution is recorded in the store.
+	fn execute_ddl(
+		&self,
+		conn_uuid: &String,
+		database: &str,
+		table: &str,
+		statements: &[String],
+	) -> IResult<()> {
+		let client = self.client(conn_uuid)?;
+		for sql in statements {
+			ddl_history::record(self.store.as_ref(), conn_uuid, database, table, sql, || {
+				client.execute(sql)
+			})?;
+		}
+		Ok(())
+	}
+
+	fn update_settings<F: FnOnce(&mut Settings)>(&self, f: F) -> IResult<()> {
+		let mut settings = self.store.settings();
+		f(&mut settings);
+		self.store.save_settings(&settings)
+	}
 }
 
+/// Result of a background request. Errors are kept as their localized message chain since
+/// messages must be `Clone`.
+pub type Fetched<T> = Result<T, String>;
+
 #[derive(Debug, Clone)]
 pub enum Message {
 	EditConnection(Option<usize>),
 	DeleteConnection(usize),
-	SelectConnection(String),
-	ShowDatabases(Option<Vec<String>>),
-	SelectDatabase(String),
-	ShowTables(Option<Vec<String>>),
-	SelectTable(String),
+	DeleteConnectionConfirmed(String),
+	ToggleConnection(String),
+	ShowDatabases(String, Fetched<Vec<String>>),
+	ToggleDatabase(String, String),
+	ShowTables(String, String, Fetched<Vec<String>>),
+	EditNavigatorSearch(String),
+	ScrollNavigator(f32),
+	SelectTable(String, String, String),
 	SelectDBType(DbType),
-	ShowTableSchema(Option<Vec<ColumnSpec>>),
+	/// Read the clipboard as a DDL dump in the given dialect.
+	PasteDdl(DbType),
+	DdlPasted(DbType, Option<String>),
+	/// Dialect and columns of a table, dropped unless the tab still shows that table.
+	ShowTableSchema {
+		id: usize,
+		conn: String,
+		database: String,
+		table: String,
+		schema: Fetched<(DbType, Vec<ColumnSpec>)>,
+	},
+	NewTab,
+	SelectTab(usize),
+	CloseTab(usize),
+	CycleTab(i32),
+	ToggleCompare,
+	/// By connection uuid, names need not be unique.
+	SelectCompareConnection(String),
+	/// Responses name what they were requested for, those of an earlier selection are dropped.
+	ShowCompareDatabases {
+		id: usize,
+		conn: String,
+		databases: Fetched<Vec<String>>,
+	},
+	SelectCompareDatabase(String),
+	ShowCompareTables {
+		id: usize,
+		conn: String,
+		database: String,
+		tables: Fetched<Vec<String>>,
+	},
+	SelectCompareTable(String),
+	ShowCompareTableSchema {
+		id: usize,
+		conn: String,
+		database: String,
+		table: String,
+		schema: Fetched<(DbType, Vec<ColumnSpec>)>,
+	},
 	SubmitConnForm,
 	CloseConnForm,
 	EditConnName(String),
@@ -53,6 +180,58 @@ pub enum Message {
 	EditConnUrl(String),
 	EditConnUsername(String),
 	EditConnPassword(String),
+	EditConnProduction(bool),
+	EditConnHost(String),
+	EditConnPort(String),
+	EditConnDatabase(String),
+	EditConnCompression(String),
+	EditConnSslMode(SslMode),
+	TestConnection,
+	ConnTested(Fetched<ConnTest>),
+	ToggleHistory,
+	EditHistoryKeyword(String),
+	ToggleHistoryConnOnly(bool),
+	ToggleHistoryFailedOnly(bool),
+	ToggleSnapshots,
+	/// Store the table of the active tab as a snapshot unless it is unchanged.
+	TakeSnapshot,
+	SelectSnapshotFrom(i64),
+	SelectSnapshotTo(i64),
+	ToggleLogs,
+	SelectLogTab(LogTab),
+	SelectLogLevel(tracing::Level),
+	ReloadLogs,
+	CopyToClipboard(String),
+	CopyDdl,
+	SaveDdl,
+	SaveDdlConfirmed(PathBuf, String),
+	Refresh,
+	ToggleTheme,
+	SelectLanguage(Language),
+	FocusSearch,
+	OpenPalette,
+	ClosePalette,
+	EditPaletteQuery(String),
+	MovePaletteSelection(i32),
+	RunPaletteSelection,
+	RunPaletteAction(Box<Message>),
+	#[cfg(feature = "json")]
+	CopyTableAsJson,
+	RerunDdl(i64),
+	RerunDdlConfirmed(i64),
+	ApplyPlan,
+	/// Execute exactly the statements shown in the confirmation, whatever happened since.
+	ApplyPlanConfirmed {
+		tab_id: usize,
+		conn_uuid: String,
+		target: Table,
+		statements: Vec<String>,
+	},
+	PlanApplied(usize, Fetched<()>),
+	EditConfirmInput(String),
+	SubmitConfirm,
+	CancelConfirm,
+	DdlExecuted(Fetched<()>),
 	IcedEvent(iced::Event),
 	CloseToast(usize),
 	Nothing,
@@ -65,15 +244,27 @@ impl Application for App {
 	type Flags = ();
 
 	fn new(_: Self::Flags) -> (Self, Command<Self::Message>) {
-		(App { all_conns: conn_conf::list_all().unwrap(), ..Default::default() }, Command::none())
+		let app = App::with_backend(Backend::default()).unwrap();
+		i18n::set_language(app.backend.store.settings().language);
+		(app, Command::none())
 	}
 
 	fn title(&self) -> String {
-		String::from("Table Sync - Iced")
+		t!("app.title")
+	}
+
+	fn theme(&self) -> Self::Theme {
+		match self.backend.store.settings().theme {
+			ThemeKind::Light => iced::theme::Theme::Light,
+			ThemeKind::Dark => iced::theme::Theme::Dark,
+		}
 	}
 
 	fn subscription(&self) -> Subscription<Self::Message> {
-		iced::subscription::events().map(Message::IcedEvent)
+		iced::subscription::events_with(|event, _| match event {
+			iced::Event::Keyboard(_) => Some(Message::IcedEvent(event)),
+			_ => None,
+		})
 	}
 
 	fn update(&mut self, message: Message) -> Command<Message> {
@@ -85,94 +276,299 @@ impl Application for App {
 						.into_iter()
 						.for_each(|cached| self.edit_conn = cached.clone())
 				}
+				self.edit_endpoint = Endpoint::parse(&self.edit_conn.url);
+				self.take_url_credentials();
 
 				self.show_conn_modal = true;
 				widget::focus_next()
 			}
 			Message::DeleteConnection(idx) => {
-				if self
-					.all_conns
-					.get(idx)
-					.map(|cached| conn_conf::delete(&cached.uuid).is_ok())
-					.is_some()
-				{
-					self.all_conns.remove(idx);
+				if let Some(conf) = self.all_conns.get(idx) {
+					let confirm = Confirm::new(
+						t!("conn.delete_title"),
+						t!("conn.delete_description", name = conf.name),
+						Message::DeleteConnectionConfirmed(conf.uuid.clone()),
+					);
+					self.confirm = Some(confirm.danger().typed(conf.production, &conf.name));
+				}
+				Command::none()
+			}
+			Message::DeleteConnectionConfirmed(uuid) => {
+				match self.backend.store.delete_conn(&uuid) {
+					Ok(_) => {
+						self.backend.clients.evict(&uuid);
+						self.navigator.conns.remove(&uuid);
+						self.all_conns.retain(|conf| conf.uuid != uuid);
+						self.workspace.forget_conn(&uuid);
+						self.follow_active_tab();
+					}
+					Err(e) => self.display_err(&e),
+				}
+				Command::none()
+			}
+			Message::ToggleConnection(uuid) => {
+				let node = self.navigator.node(&uuid);
+				node.expanded = !node.expanded;
+				if !node.expanded || node.databases.is_some() {
+					return Command::none();
+				}
+
+				load_databases(&self.backend, uuid)
+			}
+			Message::ShowDatabases(uuid, databases) => {
+				let node = self.navigator.node(&uuid);
+				match databases {
+					Ok(databases) => node.databases = Some(databases),
+					Err(e) => {
+						// Collapse so that expanding again retries.
+						node.expanded = false;
+						self.display_err_msg(e);
+					}
+				}
+				Command::none()
+			}
+			Message::ToggleDatabase(uuid, database) => {
+				let node = self.navigator.node(&uuid);
+				if !node.expanded_dbs.remove(&database) {
+					node.expanded_dbs.insert(database.clone());
+				}
+				if !node.expanded_dbs.contains(&database) || node.tables.contains_key(&database) {
+					return Command::none();
 				}
 
+				load_tables(&self.backend, uuid, database)
+			}
+			Message::ShowTables(uuid, database, tables) => {
+				let node = self.navigator.node(&uuid);
+				match tables {
+					Ok(tables) => {
+						node.tables.insert(database, tables);
+					}
+					Err(e) => {
+						node.expanded_dbs.remove(&database);
+						self.display_err_msg(e);
+					}
+				}
 				Command::none()
 			}
-			Message::SelectConnection(uuid) => {
-				self.reset_connection(&uuid);
-				Command::perform(
-					async move {
-						let conf = conn_conf::query_by_uuid(&uuid).ok()?;
-						let db_client = DBClient::get_or_init(conf.try_into().ok()?).ok()?;
-						let databases = db_client.databases().ok()?;
-						Some(databases)
-					},
-					Message::ShowDatabases,
-				)
+			Message::EditNavigatorSearch(search) => {
+				self.navigator.search = search;
+				Command::none()
+			}
+			Message::ScrollNavigator(scroll) => {
+				self.navigator.scroll = scroll;
+				Command::none()
+			}
+			Message::SelectTable(uuid, database, table) => {
+				let has_db_type =
+					self.workspace.open_table(uuid, database, table).selected_db_type.is_some();
+				self.follow_active_tab();
+				if has_db_type {
+					return Command::none();
+				}
+				self.update(Message::SelectDBType(self.backend.store.settings().default_dialect))
+			}
+			Message::SelectDBType(db_type) => {
+				let tab = self.workspace.active_mut();
+				tab.selected_db_type.replace(db_type);
+				load_schema(&self.backend, tab)
+			}
+			Message::PasteDdl(db_type) => {
+				iced::clipboard::read(move |contents| Message::DdlPasted(db_type, contents))
+			}
+			Message::DdlPasted(db_type, contents) => {
+				let sql = contents.filter(|sql| !sql.trim().is_empty());
+				match sql.map(|sql| ddl_parser::parse_create_tables(&sql, db_type)) {
+					None => self.display_err_msg(t!("content.clipboard_empty")),
+					Some(Ok(tables)) if tables.is_empty() => {
+						self.display_err_msg(t!("content.no_create_table"))
+					}
+					Some(Ok(tables)) => {
+						let default_dialect = self.backend.store.settings().default_dialect;
+						for table in tables {
+							let tab = self.workspace.open_pasted(table);
+							tab.selected_db_type = Some(default_dialect);
+						}
+						self.follow_active_tab();
+					}
+					Some(Err(e)) => self.display_err(&e),
+				}
+				Command::none()
+			}
+			Message::ShowTableSchema { id, conn, database, table, schema } => {
+				let tab =
+					self.workspace.get_mut(id).filter(|tab| tab.shows(&conn, &database, &table));
+				match (tab, schema) {
+					(Some(tab), Ok((dialect, schema))) => {
+						tab.loading_schema = false;
+						tab.dialect = Some(dialect);
+						tab.origin_table_schema = schema;
+					}
+					(Some(tab), Err(e)) => {
+						tab.loading_schema = false;
+						tab.origin_table_schema.clear();
+						self.display_err_msg(e);
+					}
+					// The tab is closed or shows another table.
+					(None, _) => {}
+				}
+				Command::none()
 			}
-			Message::ShowDatabases(databases) => {
-				self.databases = databases.unwrap_or_default();
+			Message::NewTab => {
+				self.workspace.open();
+				self.follow_active_tab();
 				Command::none()
 			}
-			Message::SelectDatabase(database) => {
-				self.reset_database(&database);
-				let uuid = self.selected_conn.clone().unwrap();
+			Message::SelectTab(index) => {
+				self.workspace.activate(index);
+				self.follow_active_tab();
+				Command::none()
+			}
+			Message::CloseTab(index) => {
+				self.workspace.close(index);
+				self.follow_active_tab();
+				Command::none()
+			}
+			Message::CycleTab(step) => {
+				self.workspace.cycle(step);
+				self.follow_active_tab();
+				Command::none()
+			}
+			Message::ToggleCompare => {
+				let tab = self.workspace.active_mut();
+				tab.show_compare = !tab.show_compare;
+				Command::none()
+			}
+			Message::SelectCompareConnection(conn) => {
+				if !self.all_conns.iter().any(|conf| conf.uuid == conn) {
+					return Command::none();
+				}
+
+				let tab = self.workspace.active_mut();
+				tab.compare =
+					CompareTarget { conn: Some(conn.clone()), loading: true, ..Default::default() };
+				let (id, backend) = (tab.id, self.backend.clone());
 				Command::perform(
 					async move {
-						let conf = conn_conf::query_by_uuid(&uuid).ok()?;
-						let db_client = DBClient::get_or_init(conf.try_into().ok()?).ok()?;
-						let tables = db_client.tables(&database).ok()?;
-						Some(tables)
+						let databases = backend.client(&conn).and_then(|client| client.databases());
+						(conn, databases.map_err(|e| e.localized()))
 					},
-					Message::ShowTables,
+					move |(conn, databases)| Message::ShowCompareDatabases { id, conn, databases },
 				)
 			}
-			Message::ShowTables(tables) => {
-				self.tables = tables.unwrap_or_default();
-				Command::none()
-			}
-			Message::SelectTable(table) => {
-				self.selected_table.replace(table);
-				self.selected_db_type.take();
+			Message::ShowCompareDatabases { id, conn, databases } => {
+				let compare = self
+					.workspace
+					.get_mut(id)
+					.map(|tab| &mut tab.compare)
+					.filter(|compare| compare.conn.as_ref() == Some(&conn));
+				match (compare, databases) {
+					(Some(compare), Ok(databases)) => {
+						compare.loading = false;
+						compare.databases = databases;
+					}
+					(Some(compare), Err(e)) => {
+						compare.loading = false;
+						self.display_err_msg(e);
+					}
+					// The tab is closed or another connection is selected.
+					(None, _) => {}
+				}
 				Command::none()
 			}
-			Message::SelectDBType(db_type) => {
-				self.selected_db_type.replace(db_type);
-				let conn_uuid = self.selected_conn.clone().unwrap();
-				let database = self.selected_db.clone().unwrap();
-				let table = self.selected_table.clone().unwrap();
+			Message::SelectCompareDatabase(database) => {
+				let tab = self.workspace.active_mut();
+				tab.compare.database.replace(database.clone());
+				tab.compare.table.take();
+				tab.compare.tables.clear();
+				tab.compare.table_schema.clear();
+				tab.compare.loading = true;
+				let (id, conn) = (tab.id, tab.compare.conn.clone().unwrap());
+				let backend = self.backend.clone();
 				Command::perform(
 					async move {
-						let conf = conn_conf::query_by_uuid(&conn_uuid).ok()?;
-						let db_client = DBClient::get_or_init(conf.try_into().ok()?).ok()?;
-						Some(db_client.table_schema(&database, &table).ok()?)
+						let tables =
+							backend.client(&conn).and_then(|client| client.tables(&database));
+						(conn, database, tables.map_err(|e| e.localized()))
+					},
+					move |(conn, database, tables)| Message::ShowCompareTables {
+						id,
+						conn,
+						database,
+						tables,
 					},
-					Message::ShowTableSchema,
 				)
 			}
-			Message::ShowTableSchema(schema) => {
-				self.origin_table_schema = schema.unwrap_or_default();
+			Message::ShowCompareTables { id, conn, database, tables } => {
+				let compare =
+					self.workspace.get_mut(id).map(|tab| &mut tab.compare).filter(|compare| {
+						compare.conn.as_ref() == Some(&conn)
+							&& compare.database.as_ref() == Some(&database)
+					});
+				match (compare, tables) {
+					(Some(compare), Ok(tables)) => {
+						compare.loading = false;
+						compare.tables = tables;
+					}
+					(Some(compare), Err(e)) => {
+						compare.loading = false;
+						self.display_err_msg(e);
+					}
+					(None, _) => {}
+				}
+				Command::none()
+			}
+			Message::SelectCompareTable(table) => {
+				let tab = self.workspace.active_mut();
+				tab.compare.table.replace(table.clone());
+				tab.compare.loading = true;
+				load_compare_schema(&self.backend, tab.id, &tab.compare, table)
+			}
+			Message::ShowCompareTableSchema { id, conn, database, table, schema } => {
+				let compare =
+					self.workspace.get_mut(id).map(|tab| &mut tab.compare).filter(|compare| {
+						compare.conn.as_ref() == Some(&conn)
+							&& compare.database.as_ref() == Some(&database)
+							&& compare.table.as_ref() == Some(&table)
+					});
+				match (compare, schema) {
+					(Some(compare), Ok((dialect, schema))) => {
+						compare.loading = false;
+						compare.dialect = Some(dialect);
+						compare.table_schema = schema;
+					}
+					(Some(compare), Err(e)) => {
+						compare.loading = false;
+						compare.table_schema.clear();
+						self.display_err_msg(e);
+					}
+					(None, _) => {}
+				}
 				Command::none()
 			}
 			Message::CloseConnForm => {
 				self.show_conn_modal = false;
 				self.edit_conn = ConnConf::default();
+				self.edit_endpoint = Endpoint::default();
 				widget::focus_next()
 			}
 			Message::SubmitConnForm => {
-				fn modify_and_fetch_all(edit_conn: &ConnConf) -> IResult<Vec<ConnConf>> {
-					conn_conf::insert_or_update(edit_conn)?;
-					conn_conf::list_all()
+				fn modify_and_fetch_all(
+					store: &dyn Store,
+					edit_conn: &ConnConf,
+				) -> IResult<Vec<ConnConf>> {
+					store.save_conn(edit_conn)?;
+					store.list_conns()
 				}
 
-				match modify_and_fetch_all(&self.edit_conn) {
+				match modify_and_fetch_all(self.backend.store.as_ref(), &self.edit_conn) {
 					Ok(conns) => {
 						self.all_conns = conns;
+						self.navigator.conns.remove(&self.edit_conn.uuid);
+						self.backend.clients.evict(&self.edit_conn.uuid);
 						self.show_conn_modal = false;
 						self.edit_conn = ConnConf::default();
+						self.edit_endpoint = Endpoint::default();
 						widget::focus_next()
 					}
 					Err(e) => {
@@ -186,10 +582,14 @@ impl Application for App {
 				Command::none()
 			}
 			Message::EditConnDbType(db_type) => {
-				self.edit_conn.db_type.replace(db_type);
+				if self.edit_conn.db_type.replace(db_type) != Some(db_type) {
+					self.edit_endpoint = Endpoint::default_for(db_type);
+					self.edit_conn.url = self.edit_endpoint.to_string();
+				}
 				Command::none()
 			}
 			Message::EditConnUrl(url) => {
+				self.edit_endpoint = Endpoint::parse(&url);
 				self.edit_conn.url = url;
 				Command::none()
 			}
@@ -201,11 +601,424 @@ impl Application for App {
 				self.edit_conn.password = password;
 				Command::none()
 			}
+			Message::EditConnProduction(production) => {
+				self.edit_conn.production = production;
+				Command::none()
+			}
+			Message::EditConnHost(host) => {
+				self.edit_endpoint.host = host;
+				self.edit_conn.url = self.edit_endpoint.to_string();
+				Command::none()
+			}
+			Message::EditConnPort(port) => {
+				if port.is_empty() {
+					self.edit_endpoint.port = None;
+				} else if let Ok(port) = port.parse() {
+					self.edit_endpoint.port = Some(port);
+				}
+				self.edit_conn.url = self.edit_endpoint.to_string();
+				Command::none()
+			}
+			Message::EditConnDatabase(database) => {
+				self.edit_endpoint.database = database;
+				self.edit_conn.url = self.edit_endpoint.to_string();
+				Command::none()
+			}
+			Message::EditConnCompression(compression) => {
+				self.edit_endpoint.set_param("compression", Some(&compression));
+				self.edit_conn.url = self.edit_endpoint.to_string();
+				Command::none()
+			}
+			Message::EditConnSslMode(mode) => {
+				self.edit_endpoint.set_ssl_mode(mode);
+				self.edit_conn.url = self.edit_endpoint.to_string();
+				Command::none()
+			}
+			Message::TestConnection => match DBParam::try_from(self.edit_conn.clone()) {
+				Ok(param) => {
+					self.testing_conn = true;
+					let clients = self.backend.clients.clone();
+					Command::perform(
+						async move { clients.test(&param).map_err(|e| e.localized()) },
+						Message::ConnTested,
+					)
+				}
+				Err(e) => {
+					self.display_err(&e);
+					Command::none()
+				}
+			},
+			Message::ConnTested(result) => {
+				self.testing_conn = false;
+				match result {
+					Ok(test) => self.toasts.push(Toast {
+						title: t!("conn.connected"),
+						body: t!("conn.tested", latency = test.latency_ms, version = test.version),
+						status: toast::Status::Success,
+					}),
+					Err(msg) => self.display_err_msg(msg),
+				}
+				Command::none()
+			}
+			Message::ToggleHistory => {
+				self.show_history = !self.show_history;
+				if self.show_history {
+					self.show_logs = false;
+					self.show_snapshots = false;
+					self.refresh_histories();
+				}
+				Command::none()
+			}
+			Message::ToggleLogs => {
+				self.show_logs = !self.show_logs;
+				if self.show_logs {
+					self.show_history = false;
+					self.show_snapshots = false;
+					self.reload_logs();
+				}
+				Command::none()
+			}
+			Message::ToggleSnapshots => {
+				self.show_snapshots = !self.show_snapshots;
+				if self.show_snapshots {
+					self.show_history = false;
+					self.show_logs = false;
+					self.refresh_snapshots();
+				}
+				Command::none()
+			}
+			Message::TakeSnapshot => {
+				let tab = self.tab();
+				let conn_uuid = match &tab.selected_conn {
+					Some(uuid) if !tab.loading_schema && !tab.origin_table_schema.is_empty() => {
+						uuid.clone()
+					}
+					_ => return Command::none(),
+				};
+
+				match snapshot::take(self.backend.store.as_ref(), &conn_uuid, &self.source_table())
+				{
+					Ok(Some(snapshot)) => self.toasts.push(Toast {
+						title: t!("snapshot.taken"),
+						body: snapshot.taken_at,
+						status: toast::Status::Success,
+					}),
+					Ok(None) => {
+						let table = self.tab().title();
+						self.toasts.push(Toast {
+							title: t!("snapshot.unchanged"),
+							body: t!("snapshot.unchanged_description", table = table),
+							status: toast::Status::Primary,
+						})
+					}
+					Err(e) => self.display_err(&e),
+				}
+				if self.show_snapshots {
+					self.refresh_snapshots();
+				}
+				Command::none()
+			}
+			Message::SelectSnapshotFrom(id) => {
+				self.snapshot_view.from = Some(id);
+				Command::none()
+			}
+			Message::SelectSnapshotTo(id) => {
+				self.snapshot_view.to = Some(id);
+				Command::none()
+			}
+			Message::SelectLogTab(tab) => {
+				self.log_view.tab = tab;
+				Command::none()
+			}
+			Message::SelectLogLevel(level) => {
+				self.log_view.level = level;
+				Command::none()
+			}
+			Message::ReloadLogs => {
+				self.reload_logs();
+				Command::none()
+			}
+			Message::EditHistoryKeyword(keyword) => {
+				self.history_filter.keyword = keyword;
+				self.refresh_histories();
+				Command::none()
+			}
+			Message::ToggleHistoryConnOnly(checked) => {
+				self.history_filter.conn_uuid =
+					if checked { self.tab().selected_conn.clone() } else { None };
+				self.refresh_histories();
+				Command::none()
+			}
+			Message::ToggleHistoryFailedOnly(checked) => {
+				self.history_filter.failed_only = checked;
+				self.refresh_histories();
+				Command::none()
+			}
+			Message::CopyToClipboard(content) => iced::clipboard::write(content),
+			Message::CopyDdl => {
+				if self.tab().origin_table_schema.is_empty() {
+					return Command::none();
+				}
+
+				match ddl_preview::generate(self) {
+					Ok(sql) => iced::clipboard::write(sql),
+					Err(e) => {
+						self.display_err(&e);
+						Command::none()
+					}
+				}
+			}
+			Message::SaveDdl => {
+				match ddl_preview::generate(self) {
+					Ok(sql) => {
+						let path = ddl_preview::file_path(self);
+						self.confirm = Some(Confirm::new(
+							t!("ddl.save_title"),
+							t!("ddl.save_description", path = path.display()),
+							Message::SaveDdlConfirmed(path, sql),
+						));
+					}
+					Err(e) => self.display_err(&e),
+				}
+				Command::none()
+			}
+			Message::SaveDdlConfirmed(path, sql) => {
+				match self.backend.store.create_file(&path, &sql) {
+					Ok(_) => self.toasts.push(Toast {
+						title: t!("ddl.saved"),
+						body: path.display().to_string(),
+						status: toast::Status::Success,
+					}),
+					Err(e) => self.display_err(&e),
+				}
+				Command::none()
+			}
+			#[cfg(feature = "json")]
+			Message::CopyTableAsJson => match self.table_as_json() {
+				Ok(json) => iced::clipboard::write(json),
+				Err(e) => {
+					self.display_err(&e);
+					Command::none()
+				}
+			},
+			Message::RerunDdl(id) => {
+				if let Some(history) = self.histories.iter().find(|history| history.id == id) {
+					let (name, production) = self.conn_label(&history.conn_uuid);
+					let confirm = Confirm::new(
+						t!("ddl.rerun_title"),
+						t!(
+							"ddl.rerun_description",
+							database = history.database,
+							table = history.table,
+							name = name,
+							statement = history.statement
+						),
+						Message::RerunDdlConfirmed(id),
+					);
+					self.confirm = Some(confirm.danger().typed(production, &name));
+				}
+				Command::none()
+			}
+			Message::RerunDdlConfirmed(id) => {
+				let backend = self.backend.clone();
+				Command::perform(
+					async move {
+						fn rerun(backend: &Backend, id: i64) -> IResult<()> {
+							let history = backend.store.query_history(id)?;
+							backend.execute_ddl(
+								&history.conn_uuid,
+								&history.database,
+								&history.table,
+								std::slice::from_ref(&history.statement),
+							)
+						}
+
+						rerun(&backend, id).map_err(|e| e.localized())
+					},
+					Message::DdlExecuted,
+				)
+			}
+			Message::ApplyPlan => {
+				let target = ddl_preview::compared_table(self);
+				let conn_uuid = self.tab().compare.conn.clone();
+				match (target, conn_uuid, ddl_preview::statements(self)) {
+					(Some(target), Some(conn_uuid), Ok(statements)) if !statements.is_empty() => {
+						let (name, production) = self.conn_label(&conn_uuid);
+						let confirm = Confirm::new(
+							t!("ddl.apply_title"),
+							t!(
+								"ddl.apply_description",
+								count = statements.len(),
+								database = target.database,
+								table = target.name,
+								name = name,
+								statements = statements.join(";\n")
+							),
+							Message::ApplyPlanConfirmed {
+								tab_id: self.tab().id,
+								conn_uuid,
+								target,
+								statements,
+							},
+						);
+						self.confirm = Some(confirm.danger().typed(production, &name));
+					}
+					(_, _, Err(e)) => self.display_err(&e),
+					_ => {}
+				}
+				Command::none()
+			}
+			Message::ApplyPlanConfirmed { tab_id, conn_uuid, target, statements } => {
+				let backend = self.backend.clone();
+				Command::perform(
+					async move {
+						backend
+							.execute_ddl(&conn_uuid, &target.database, &target.name, &statements)
+							.map_err(|e| e.localized())
+					},
+					move |result| Message::PlanApplied(tab_id, result),
+				)
+			}
+			Message::PlanApplied(id, result) => match result {
+				Ok(_) => {
+					self.toasts.push(Toast {
+						title: t!("ddl.applied"),
+						body: t!("ddl.up_to_date"),
+						status: toast::Status::Success,
+					});
+					match self.workspace.get_mut(id) {
+						Some(tab) => match tab.compare.table.clone() {
+							Some(table) => {
+								tab.compare.loading = true;
+								load_compare_schema(&self.backend, id, &tab.compare, table)
+							}
+							None => Command::none(),
+						},
+						None => Command::none(),
+					}
+				}
+				Err(e) => {
+					self.display_err_msg(e);
+					Command::none()
+				}
+			},
+			Message::EditConfirmInput(input) => {
+				if let Some(confirm) = &mut self.confirm {
+					confirm.input = input;
+				}
+				Command::none()
+			}
+			Message::SubmitConfirm => match self.confirm.take() {
+				Some(confirm) if confirm.is_satisfied() => self.update(confirm.on_confirm),
+				confirm => {
+					self.confirm = confirm;
+					Command::none()
+				}
+			},
+			Message::CancelConfirm => {
+				self.confirm = None;
+				Command::none()
+			}
+			Message::DdlExecuted(result) => {
+				if let Err(e) = result {
+					self.display_err_msg(e);
+				}
+				self.refresh_histories();
+				Command::none()
+			}
+			Message::Refresh => {
+				match self.backend.store.list_conns() {
+					Ok(conns) => self.all_conns = conns,
+					Err(e) => self.display_err(&e),
+				}
+
+				let all_conns = &self.all_conns;
+				self.navigator
+					.conns
+					.retain(|uuid, _| all_conns.iter().any(|conf| &conf.uuid == uuid));
+				let mut commands = Vec::new();
+				for (uuid, node) in self.navigator.conns.iter_mut() {
+					node.databases = None;
+					node.tables.clear();
+					if node.expanded {
+						commands.push(load_databases(&self.backend, uuid.clone()));
+					}
+					for database in &node.expanded_dbs {
+						commands.push(load_tables(&self.backend, uuid.clone(), database.clone()));
+					}
+				}
+				if self.show_logs {
+					self.reload_logs();
+				}
+				for tab in self.workspace.tabs.iter_mut() {
+					if tab.selected_db_type.is_some() {
+						commands.push(load_schema(&self.backend, tab));
+					}
+				}
+
+				Command::batch(commands)
+			}
+			Message::ToggleTheme => {
+				if let Err(e) = self
+					.backend
+					.update_settings(|settings| settings.theme = settings.theme.toggled())
+				{
+					self.display_err(&e);
+				}
+				Command::none()
+			}
+			Message::SelectLanguage(language) => {
+				match self.backend.update_settings(|settings| settings.language = language) {
+					Ok(()) => i18n::set_language(language),
+					Err(e) => self.display_err(&e),
+				}
+				Command::none()
+			}
+			Message::FocusSearch => text_input::focus(sidebar::search_input_id()),
+			Message::OpenPalette => {
+				self.palette = Some(Palette::default());
+				text_input::focus(palette::input_id())
+			}
+			Message::ClosePalette => {
+				self.palette = None;
+				Command::none()
+			}
+			Message::EditPaletteQuery(query) => {
+				if let Some(palette) = &mut self.palette {
+					palette.query = query;
+					palette.selected = 0;
+				}
+				Command::none()
+			}
+			Message::MovePaletteSelection(step) => {
+				let matched = self
+					.palette
+					.as_ref()
+					.map_or(0, |palette| palette::matched(self, &palette.query).len());
+				if let Some(palette) = self.palette.as_mut().filter(|_| matched > 0) {
+					palette.selected =
+						(palette.selected as i32 + step).rem_euclid(matched as i32) as usize;
+				}
+				Command::none()
+			}
+			Message::RunPaletteSelection => {
+				let action = self.palette.as_ref().and_then(|palette| {
+					palette::matched(self, &palette.query).into_iter().nth(palette.selected)
+				});
+				match action {
+					Some((_, message)) => self.update(Message::RunPaletteAction(Box::new(message))),
+					None => Command::none(),
+				}
+			}
+			Message::RunPaletteAction(message) => {
+				self.palette = None;
+				self.update(*message)
+			}
 			Message::CloseToast(index) => {
 				self.toasts.remove(index);
 				Command::none()
 			}
-			Message::IcedEvent(e) => event_handler::handle(e),
+			Message::IcedEvent(e) => event_handler::handle(self, e),
 			_ => Command::none(),
 		}
 	}
@@ -216,35 +1029,213 @@ impl Application for App {
 			.push(Row::new().push(sidebar::view(self)).push(content::view(self)))
 			.padding(10)
 			.width(Length::Fill);
+		let user_view: Element<Message> = match &self.palette {
+			Some(palette) => Modal::new(user_view, palette::view(self, palette))
+				.on_blur(Message::ClosePalette)
+				.into(),
+			None => user_view.into(),
+		};
+		let user_view = match &self.confirm {
+			Some(confirm) => Modal::new(user_view, confirm::view(self, confirm))
+				.on_blur(Message::CancelConfirm)
+				.into(),
+			None => user_view,
+		};
 
-		toast::Manager::new(user_view, &self.toasts, Message::CloseToast).timeout(2).into()
+		toast::Manager::new(user_view, &self.toasts, Message::CloseToast)
+			.timeout(self.backend.store.settings().toast_timeout)
+			.into()
 	}
 }
 
 impl App {
-	pub fn reset_connection(&mut self, conn_uuid: &String) {
-		self.selected_conn.replace(conn_uuid.clone());
-		self.selected_db.take();
-		self.selected_table.take();
-		self.selected_db_type.take();
-		self.tables.clear();
-		self.databases.clear();
-		self.origin_table_schema.clear();
+	/// An app reading and writing through `backend`, starting with the connections it stores.
+	pub fn with_backend(backend: Backend) -> IResult<App> {
+		Ok(App { all_conns: backend.store.list_conns()?, backend, ..Default::default() })
+	}
+
+	/// The active tab.
+	pub fn tab(&self) -> &Tab {
+		self.workspace.active()
+	}
+
+	/// The selected table of the active tab in the dialect of its connection, or the pasted one.
+	pub fn source_table(&self) -> Table {
+		let tab = self.tab();
+		if let Some(pasted) = &tab.pasted {
+			return pasted.clone();
+		}
+
+		Table::new(
+			tab.selected_db.clone().unwrap_or_default(),
+			tab.selected_table.clone().unwrap_or_default(),
+			self.source_type(),
+			tab.origin_table_schema.clone(),
+		)
+	}
+
+	/// Dialect of the selected table of the active tab.
+	pub fn source_type(&self) -> DbType {
+		let tab = self.tab();
+		match &tab.pasted {
+			Some(pasted) => pasted.r#type,
+			None => tab.dialect.unwrap_or(DbType::Internal),
+		}
+	}
+
+	#[cfg(feature = "json")]
+	pub fn table_as_json(&self) -> IResult<String> {
+		Ok(serde_json::to_string_pretty(&self.source_table())?)
+	}
+
+	/// Name of the connection and whether it is tagged as production.
+	pub fn conn_label(&self, uuid: &str) -> (String, bool) {
+		self.all_conns
+			.iter()
+			.find(|conf| conf.uuid == uuid)
+			.map_or((uuid.to_owned(), false), |conf| (conf.name.clone(), conf.production))
+	}
+
+	/// Move credentials written in the url of a MySQL or ClickHouse connection being edited to its
+	/// own fields, the url is rewritten without them and would otherwise lose them.
+	fn take_url_credentials(&mut self) {
+		let endpoint = &mut self.edit_endpoint;
+		if endpoint.username.is_empty()
+			|| !matches!(self.edit_conn.db_type, Some(DbType::MySQL | DbType::ClickHouse))
+		{
+			return;
+		}
+
+		self.edit_conn.username = std::mem::take(&mut endpoint.username);
+		self.edit_conn.password = std::mem::take(&mut endpoint.password);
+		self.edit_conn.url = endpoint.to_string();
+	}
+
+	pub fn refresh_histories(&mut self) {
+		match self.backend.store.query_histories(&self.history_filter) {
+			Ok(histories) => self.histories = histories,
+			Err(e) => self.display_err(&e),
+		}
+	}
+
+	/// Load the snapshots of the table of the active tab.
+	pub fn refresh_snapshots(&mut self) {
+		let tab = self.tab();
+		let snapshot_view = match (&tab.selected_conn, &tab.selected_db, &tab.selected_table) {
+			(Some(conn), Some(database), Some(table)) => SnapshotView::load(
+				self.backend.store.as_ref(),
+				conn.clone(),
+				database.clone(),
+				table.clone(),
+			),
+			_ => Ok(SnapshotView::default()),
+		};
+		match snapshot_view {
+			Ok(snapshot_view) => self.snapshot_view = snapshot_view,
+			Err(e) => self.display_err(&e),
+		}
 	}
 
-	pub fn reset_database(&mut self, database: &String) {
-		self.selected_db.replace(database.clone());
-		self.selected_table.take();
-		self.selected_db_type.take();
-		self.tables.clear();
-		self.origin_table_schema.clear();
+	/// Keeps the snapshot panel on the table of the active tab.
+	fn follow_active_tab(&mut self) {
+		if self.show_snapshots {
+			self.refresh_snapshots();
+		}
+	}
+
+	pub fn reload_logs(&mut self) {
+		self.log_view.queries = query_log::records();
+		match logs::load() {
+			Ok(lines) => self.log_view.lines = lines,
+			Err(e) => self.display_err(&e),
+		}
 	}
 
 	pub fn display_err(&mut self, e: &IError) {
+		self.display_err_msg(e.localized());
+	}
+
+	pub fn display_err_msg(&mut self, msg: String) {
 		self.toasts.push(Toast {
-			title: "Error".into(),
-			body: e.to_string(),
+			title: t!("common.error"),
+			body: msg,
 			status: toast::Status::Danger,
 		});
 	}
 }
+
+/// Fetch the schema of the table selected in `tab`.
+fn load_schema(backend: &Backend, tab: &mut Tab) -> Command<Message> {
+	let (conn_uuid, database, table) =
+		match (&tab.selected_conn, &tab.selected_db, &tab.selected_table) {
+			(Some(conn), Some(database), Some(table)) => {
+				(conn.clone(), database.clone(), table.clone())
+			}
+			_ => return Command::none(),
+		};
+
+	tab.loading_schema = true;
+	let (id, backend) = (tab.id, backend.clone());
+	Command::perform(
+		async move {
+			let schema = backend.client(&conn_uuid).and_then(|client| {
+				Ok((client.dialect()?, client.table_schema(&database, &table)?))
+			});
+			(conn_uuid, database, table, schema.map_err(|e| e.localized()))
+		},
+		move |(conn, database, table, schema)| Message::ShowTableSchema {
+			id,
+			conn,
+			database,
+			table,
+			schema,
+		},
+	)
+}
+
+fn load_compare_schema(
+	backend: &Backend,
+	id: usize,
+	compare: &CompareTarget,
+	table: String,
+) -> Command<Message> {
+	let (conn, database) = (compare.conn.clone().unwrap(), compare.database.clone().unwrap());
+	let backend = backend.clone();
+	Command::perform(
+		async move {
+			let schema = backend.client(&conn).and_then(|client| {
+				Ok((client.dialect()?, client.table_schema(&database, &table)?))
+			});
+			(conn, database, table, schema.map_err(|e| e.localized()))
+		},
+		move |(conn, database, table, schema)| Message::ShowCompareTableSchema {
+			id,
+			conn,
+			database,
+			table,
+			schema,
+		},
+	)
+}
+
+fn load_databases(backend: &Backend, uuid: String) -> Command<Message> {
+	let backend = backend.clone();
+	Command::perform(
+		async move {
+			let databases = backend.client(&uuid).and_then(|client| client.databases());
+			(uuid, databases.map_err(|e| e.localized()))
+		},
+		|(uuid, databases)| Message::ShowDatabases(uuid, databases),
+	)
+}
+
+fn load_tables(backend: &Backend, uuid: String, database: String) -> Command<Message> {
+	let backend = backend.clone();
+	Command::perform(
+		async move {
+			let tables = backend.client(&uuid).and_then(|client| client.tables(&database));
+			(uuid, database, tables.map_err(|e| e.localized()))
+		},
+		|(uuid, database, tables)| Message::ShowTables(uuid, database, tables),
+	)
+}
@@ -0,0 +1,160 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::store::conn_conf::ConnConf;
+
+/// State of the connection → database → table tree in the sidebar.
+#[derive(Debug, Default)]
+pub struct Navigator {
+	pub search: String,
+	/// Keyed by connection uuid.
+	pub conns: HashMap<String, ConnNode>,
+	/// Relative vertical scroll offset, from 0 to 1.
+	pub scroll: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct ConnNode {
+	pub expanded: bool,
+	/// Loaded when the connection is first expanded.
+	pub databases: Option<Vec<String>>,
+	pub expanded_dbs: HashSet<String>,
+	/// Tables of each database, loaded when the database is first expanded.
+	pub tables: HashMap<String, Vec<String>>,
+}
+
+/// A visible row of the tree.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Node<'a> {
+	Conn {
+		index: usize,
+		uuid: &'a str,
+		name: &'a str,
+		expanded: bool,
+	},
+	Database {
+		conn: &'a str,
+		name: &'a str,
+		expanded: bool,
+	},
+	Table {
+		conn: &'a str,
+		database: &'a str,
+		name: &'a str,
+	},
+	/// Placeholder of children which are still being loaded.
+	Loading {
+		depth: u16,
+	},
+}
+
+impl Navigator {
+	pub fn node(&mut self, conn: &str) -> &mut ConnNode {
+		self.conns.entry(conn.to_owned()).or_default()
+	}
+
+	/// Rows to render, in order. While searching, nodes which do not match are hidden unless
+	/// one of their loaded descendants matches, in which case they are shown expanded.
+	pub fn flatten<'a>(&'a self, conns: &'a [ConnConf]) -> Vec<Node<'a>> {
+		let keyword = self.search.trim().to_lowercase();
+		let mut nodes = Vec::new();
+		for (index, conf) in conns.iter().enumerate() {
+			let state = self.conns.get(&conf.uuid);
+			let expanded = state.map_or(false, |state| state.expanded);
+			let matched = is_match(&conf.name, &keyword);
+			let children = match state {
+				Some(state) if matched && expanded => state.database_nodes(&conf.uuid, ""),
+				Some(state) if !matched => state.database_nodes(&conf.uuid, &keyword),
+				_ => Vec::new(),
+			};
+
+			if matched || !children.is_empty() {
+				nodes.push(Node::Conn {
+					index,
+					uuid: &conf.uuid,
+					name: &conf.name,
+					expanded: expanded || !matched,
+				});
+				nodes.extend(children);
+			}
+		}
+
+		nodes
+	}
+}
+
+impl ConnNode {
+	fn database_nodes<'a>(&'a self, conn: &'a str, keyword: &str) -> Vec<Node<'a>> {
+		let Some(databases) = &self.databases else {
+			return if keyword.is_empty() { vec![Node::Loading { depth: 1 }] } else { Vec::new() };
+		};
+
+		let mut nodes = Vec::new();
+		for database in databases {
+			let expanded = self.expanded_dbs.contains(database);
+			let matched = is_match(database, keyword);
+			let tables = match self.tables.get(database) {
+				_ if matched && !expanded => Vec::new(),
+				None if matched => vec![Node::Loading { depth: 2 }],
+				None => Vec::new(),
+				Some(tables) => {
+					let keyword = if matched { "" } else { keyword };
+					tables
+						.iter()
+						.filter(|table| is_match(table, keyword))
+						.map(|name| Node::Table { conn, database, name })
+						.collect()
+				}
+			};
+
+			if matched || !tables.is_empty() {
+				nodes.push(Node::Database { conn, name: database, expanded: expanded || !matched });
+				nodes.extend(tables);
+			}
+		}
+
+		nodes
+	}
+}
+
+/// `keyword` is lower case, an empty keyword matches everything.
+fn is_match(name: &str, keyword: &str) -> bool {
+	keyword.is_empty() || name.to_lowercase().contains(keyword)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Navigator, Node};
+	use crate::store::conn_conf::ConnConf;
+
+	#[test]
+	fn test_flatten() {
+		let conns = vec![
+			ConnConf { uuid: "1".to_owned(), name: "mysql".to_owned(), ..Default::default() },
+			ConnConf { uuid: "2".to_owned(), name: "clickhouse".to_owned(), ..Default::default() },
+		];
+		let mut navigator = Navigator::default();
+		let node = navigator.node("1");
+		node.expanded = true;
+		node.databases = Some(vec!["app".to_owned(), "log".to_owned()]);
+		node.expanded_dbs.insert("app".to_owned());
+		node.tables.insert("app".to_owned(), vec!["user".to_owned(), "order".to_owned()]);
+		navigator.node("2").expanded = true;
+
+		let nodes = navigator.flatten(&conns);
+		assert_eq!(nodes.len(), 7);
+		assert_eq!(nodes[2], Node::Table { conn: "1", database: "app", name: "user" });
+		assert_eq!(nodes[4], Node::Database { conn: "1", name: "log", expanded: false });
+		assert_eq!(nodes[6], Node::Loading { depth: 1 });
+
+		navigator.search = "USER".to_owned();
+		navigator.node("1").expanded = false;
+		let nodes = navigator.flatten(&conns);
+		assert_eq!(nodes.len(), 3);
+		assert!(matches!(nodes[0], Node::Conn { index: 0, expanded: true, .. }));
+		assert_eq!(nodes[1], Node::Database { conn: "1", name: "app", expanded: true });
+		assert_eq!(nodes[2], Node::Table { conn: "1", database: "app", name: "user" });
+
+		navigator.search = "click".to_owned();
+		assert_eq!(navigator.flatten(&conns).len(), 2);
+	}
+}
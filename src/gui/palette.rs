@@ -0,0 +1,197 @@
+use iced::{
+	theme,
+	widget::{button, column, container, text, text_input, Column, Container},
+	Length, Renderer,
+};
+
+use crate::{conf::Language, i18n::t, mapping::database::DbType};
+
+use super::{App, Message};
+
+/// Number of matched actions listed at once.
+pub const MAX_ITEMS: usize = 12;
+
+/// State of the `Ctrl+K` command palette.
+#[derive(Debug, Default)]
+pub struct Palette {
+	pub query: String,
+	/// Index into the matched actions.
+	pub selected: usize,
+}
+
+pub fn input_id() -> text_input::Id {
+	text_input::Id::new("command-palette")
+}
+
+pub fn view<'a>(app: &App, palette: &'a Palette) -> Container<'a, Message, Renderer> {
+	let items = matched(app, &palette.query).into_iter().enumerate().fold(
+		Column::new().spacing(2),
+		|base, (index, (label, message))| {
+			base.push(
+				button(text(label))
+					.width(Length::Fill)
+					.style(if index == palette.selected {
+						theme::Button::Primary
+					} else {
+						theme::Button::Text
+					})
+					.on_press(Message::RunPaletteAction(Box::new(message))),
+			)
+		},
+	);
+
+	container(
+		column![
+			text_input(&t!("palette.placeholder"), &palette.query, Message::EditPaletteQuery)
+				.id(input_id())
+				.on_submit(Message::RunPaletteSelection),
+			items
+		]
+		.spacing(10),
+	)
+	.padding(20)
+	.width(Length::Fixed(480.0))
+	.style(theme::Container::Box)
+}
+
+/// Actions matching the query, best first.
+pub fn matched(app: &App, query: &str) -> Vec<(String, Message)> {
+	let mut scored = actions(app)
+		.into_iter()
+		.filter_map(|(label, message)| Some((fuzzy_score(query, &label)?, label, message)))
+		.collect::<Vec<_>>();
+	scored.sort_by(|a, b| b.0.cmp(&a.0));
+	scored.into_iter().take(MAX_ITEMS).map(|(_, label, message)| (label, message)).collect()
+}
+
+/// Every action available in the current state, including those of the keyboard shortcuts.
+pub fn actions(app: &App) -> Vec<(String, Message)> {
+	let mut actions = vec![
+		(t!("palette.new_conn"), Message::EditConnection(None)),
+		(t!("palette.refresh"), Message::Refresh),
+		(t!("palette.search_tables"), Message::FocusSearch),
+		(t!("palette.toggle_history"), Message::ToggleHistory),
+		(t!("palette.toggle_snapshots"), Message::ToggleSnapshots),
+		(t!("palette.toggle_logs"), Message::ToggleLogs),
+		(t!("palette.toggle_theme"), Message::ToggleTheme),
+		(t!("palette.new_tab"), Message::NewTab),
+		(t!("palette.close_tab"), Message::CloseTab(app.workspace.active)),
+		(t!("palette.next_tab"), Message::CycleTab(1)),
+		(t!("palette.previous_tab"), Message::CycleTab(-1)),
+	];
+
+	if app.show_logs {
+		actions.push((t!("palette.reload_logs"), Message::ReloadLogs));
+	}
+
+	let current = app.backend.store.settings().language;
+	for language in Language::ALL.iter().filter(|language| **language != current) {
+		actions.push((
+			t!("palette.language", language = language),
+			Message::SelectLanguage(*language),
+		));
+	}
+
+	for db_type in DbType::PARSABLE {
+		actions.push((t!("palette.paste_ddl", db_type = db_type), Message::PasteDdl(*db_type)));
+	}
+
+	for (index, tab) in app.workspace.tabs.iter().enumerate() {
+		actions.push((t!("palette.switch_tab", title = tab.title()), Message::SelectTab(index)));
+	}
+
+	for (index, conf) in app.all_conns.iter().enumerate() {
+		actions.push((
+			t!("palette.toggle_conn", name = conf.name),
+			Message::ToggleConnection(conf.uuid.clone()),
+		));
+		actions.push((
+			t!("palette.edit_conn", name = conf.name),
+			Message::EditConnection(Some(index)),
+		));
+
+		let Some(node) = app.navigator.conns.get(&conf.uuid) else { continue };
+		for database in node.databases.iter().flatten() {
+			for table in node.tables.get(database).into_iter().flatten() {
+				actions.push((
+					t!("palette.open_table", database = database, table = table, name = conf.name),
+					Message::SelectTable(conf.uuid.clone(), database.clone(), table.clone()),
+				));
+			}
+		}
+	}
+
+	if app.tab().selected_table.is_some() {
+		for db_type in DbType::ALL {
+			actions
+				.push((t!("palette.convert", db_type = db_type), Message::SelectDBType(*db_type)));
+		}
+		actions.push((t!("palette.toggle_compare"), Message::ToggleCompare));
+	}
+
+	if !app.tab().origin_table_schema.is_empty() {
+		actions.push((t!("palette.copy_ddl"), Message::CopyDdl));
+		actions.push((t!("palette.save_ddl"), Message::SaveDdl));
+		if app.tab().selected_conn.is_some() {
+			actions.push((t!("palette.take_snapshot"), Message::TakeSnapshot));
+		}
+		#[cfg(feature = "json")]
+		actions.push((t!("palette.copy_json"), Message::CopyTableAsJson));
+	}
+
+	actions
+}
+
+/// Score of `query` as a case-insensitive subsequence of `target`, `None` if it is not one.
+/// Each character prefers to continue the previous match, then a word start, then anything.
+/// Consecutive characters and word starts score higher, gaps lower.
+fn fuzzy_score(query: &str, target: &str) -> Option<i32> {
+	let target = target.chars().collect::<Vec<_>>();
+	let is_match = |i: usize, c: char| target[i].to_lowercase().eq(c.to_lowercase());
+	let is_word_start = |i: usize| {
+		i == 0
+			|| !target[i - 1].is_alphanumeric()
+			|| (target[i - 1].is_lowercase() && target[i].is_uppercase())
+	};
+
+	let mut score = 0;
+	let mut pos = 0;
+	let mut last = None;
+	for c in query.chars().filter(|c| !c.is_whitespace()) {
+		let consecutive = last.is_some() && pos < target.len() && is_match(pos, c);
+		let found = if consecutive {
+			pos
+		} else {
+			(pos..target.len())
+				.find(|&i| is_match(i, c) && is_word_start(i))
+				.or_else(|| (pos..target.len()).find(|&i| is_match(i, c)))?
+		};
+
+		score += 1 - (found - pos).min(3) as i32;
+		if is_word_start(found) {
+			score += 3;
+		}
+		if consecutive {
+			score += 5;
+		}
+		last = Some(found);
+		pos = found + 1;
+	}
+
+	Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::fuzzy_score;
+
+	#[test]
+	fn test_fuzzy_score() {
+		assert!(fuzzy_score("", "Refresh").is_some());
+		assert!(fuzzy_score("nc", "New connection").is_some());
+		assert!(fuzzy_score("cn", "Refresh").is_none());
+		assert!(fuzzy_score("ref", "Refresh") > fuzzy_score("ref", "Open table users.ref_id"));
+		assert!(fuzzy_score("ch", "Convert to ClickHouse") > fuzzy_score("ch", "Search tables"));
+		assert!(fuzzy_score("tch", "Convert to ClickHouse") > fuzzy_score("tch", "Search tables"));
+	}
+}
@@ -1,54 +1,119 @@
 use iced::{
 	theme,
-	widget::{button, container, row, text, Column, Container},
-	Alignment, Length, Renderer,
+	widget::{button, container, row, scrollable, text, text_input, Column, Container, Row, Space},
+	Alignment, Application, Length, Renderer,
 };
 
-use crate::gui::style::icon::{delete_icon, edit_icon};
+use crate::{
+	gui::style::icon::{delete_icon, edit_icon},
+	i18n::t,
+};
 
 use super::{
-	style::{border_style, button_style},
+	navigator::Node,
+	style::{border_style, button_style, muted_color},
 	App, Message,
 };
 
-pub fn view<'a>(app: &App) -> Container<'a, Message, Renderer> {
-	let column_list = app
-		.all_conns
+const ROW_HEIGHT: f32 = 32.0;
+
+const INDENT: f32 = 16.0;
+
+/// Rows rendered on each side of the scroll position, enough to fill a tall window. Rows out of
+/// this range are replaced by spacers so that huge catalogs stay cheap to lay out.
+const WINDOW_ROWS: usize = 100;
+
+pub fn search_input_id() -> text_input::Id {
+	text_input::Id::new("navigator-search")
+}
+
+pub fn view(app: &App) -> Container<'_, Message, Renderer> {
+	let nodes = app.navigator.flatten(&app.all_conns);
+	let anchor = (app.navigator.scroll * nodes.len() as f32) as usize;
+	let start = anchor.saturating_sub(WINDOW_ROWS);
+	let end = (anchor + WINDOW_ROWS).min(nodes.len());
+
+	let rows = nodes[start.min(end)..end]
 		.iter()
-		.enumerate()
-		.fold(Column::new(), |base, col| {
-			base.push(
-				container(
-					row![
-						button(text(&col.1.name).size(20))
-							.width(Length::Fill)
-							.style(button_style(
-								matches!(&app.selected_conn, Some(uuid) if uuid == &col.1.uuid)
-							))
-							.on_press(Message::SelectConnection(col.1.uuid.clone())),
-						button(edit_icon())
-							.style(theme::Button::Secondary)
-							.on_press(Message::EditConnection(Some(col.0))),
-						button(delete_icon())
-							.style(theme::Button::Secondary)
-							.on_press(Message::DeleteConnection(col.0))
-					]
-					.spacing(5),
-				)
-				.width(Length::Fill)
-				.padding(5)
-				.center_y()
-				.style(border_style()),
-			)
-		})
+		.fold(
+			Column::new().push(Space::with_height(Length::Fixed(start as f32 * ROW_HEIGHT))),
+			|base, node| base.push(node_row(app, node)),
+		)
+		.push(Space::with_height(Length::Fixed((nodes.len() - end) as f32 * ROW_HEIGHT)))
+		.width(Length::Fill);
+
+	let tree = Column::new()
+		.push(
+			text_input(&t!("sidebar.search"), &app.navigator.search, Message::EditNavigatorSearch)
+				.id(search_input_id()),
+		)
+		.push(
+			scrollable(rows)
+				.height(Length::Fill)
+				.on_scroll(|offset| Message::ScrollNavigator(offset.y)),
+		)
 		.spacing(5)
 		.width(Length::Fill)
 		.height(Length::Fill)
 		.align_items(Alignment::Center);
 
-	container(column_list)
+	container(tree)
 		.width(Length::FillPortion(1))
 		.height(Length::Fill)
 		.padding(5)
 		.style(border_style())
 }
+
+fn node_row<'a>(app: &App, node: &Node<'a>) -> Row<'a, Message, Renderer> {
+	let row = match *node {
+		Node::Conn { index, uuid, name, expanded } => row![
+			button(row![text(marker(expanded)), text(name)].spacing(5))
+				.width(Length::Fill)
+				.style(button_style(
+					matches!(&app.tab().selected_conn, Some(selected) if selected == uuid)
+				))
+				.on_press(Message::ToggleConnection(uuid.to_owned())),
+			button(edit_icon())
+				.style(theme::Button::Secondary)
+				.on_press(Message::EditConnection(Some(index))),
+			button(delete_icon())
+				.style(theme::Button::Secondary)
+				.on_press(Message::DeleteConnection(index))
+		],
+		Node::Database { conn, name, expanded } => row![
+			Space::with_width(Length::Fixed(INDENT)),
+			button(row![text(marker(expanded)), text(name)].spacing(5))
+				.width(Length::Fill)
+				.style(theme::Button::Text)
+				.on_press(Message::ToggleDatabase(conn.to_owned(), name.to_owned())),
+		],
+		Node::Table { conn, database, name } => {
+			let selected = app.tab().shows(conn, database, name);
+			row![
+				Space::with_width(Length::Fixed(INDENT * 2.0)),
+				button(text(name))
+					.width(Length::Fill)
+					.style(if selected { theme::Button::Primary } else { theme::Button::Text })
+					.on_press(Message::SelectTable(
+						conn.to_owned(),
+						database.to_owned(),
+						name.to_owned()
+					)),
+			]
+		}
+		Node::Loading { depth } => row![
+			Space::with_width(Length::Fixed(INDENT * depth as f32)),
+			text(t!("common.loading")).style(theme::Text::Color(muted_color(&app.theme()))),
+		],
+	};
+
+	row.spacing(5).height(Length::Fixed(ROW_HEIGHT)).align_items(Alignment::Center)
+}
+
+fn marker(expanded: bool) -> &'static str {
+	if expanded {
+		"-"
+	} else {
+		"+"
+	}
+}
@@ -0,0 +1,172 @@
+use iced::{
+	theme,
+	widget::{button, column, container, row, scrollable, text, Column, Container, Row},
+	Alignment, Length, Renderer,
+};
+
+use crate::{
+	error::IResult,
+	i18n::t,
+	mapping::{
+		diff::{self, ColumnDiff},
+		table::Table,
+	},
+	store::{snapshot::Snapshot, Store},
+};
+
+use super::{
+	compare,
+	style::{border_style, button_style},
+	App, Message,
+};
+
+/// Snapshots of one table and the two of them being compared.
+#[derive(Debug, Default)]
+pub struct SnapshotView {
+	/// Connection uuid, database and name of the table.
+	pub table: Option<(String, String, String)>,
+	/// Newest first, with the table each one holds.
+	pub snapshots: Vec<(Snapshot, Table)>,
+	/// Older side of the comparison.
+	pub from: Option<i64>,
+	/// Newer side of the comparison.
+	pub to: Option<i64>,
+}
+
+impl SnapshotView {
+	/// Snapshots of a table in `store`, the two latest ones are compared.
+	pub fn load(
+		store: &dyn Store,
+		conn_uuid: String,
+		database: String,
+		table: String,
+	) -> IResult<SnapshotView> {
+		let snapshots = store
+			.query_snapshots(&conn_uuid, &database, &table)?
+			.into_iter()
+			.map(|snapshot| snapshot.table().map(|table| (snapshot, table)))
+			.collect::<IResult<Vec<_>>>()?;
+		let ids = snapshots.iter().map(|(snapshot, _)| snapshot.id).collect::<Vec<_>>();
+
+		Ok(SnapshotView {
+			table: Some((conn_uuid, database, table)),
+			snapshots,
+			from: ids.get(1).copied(),
+			to: ids.first().copied(),
+		})
+	}
+
+	fn get(&self, id: Option<i64>) -> Option<&(Snapshot, Table)> {
+		self.snapshots.iter().find(|(snapshot, _)| Some(snapshot.id) == id)
+	}
+
+	/// Columns changed from `from` to `to`, compared in the dialect of `to`.
+	pub fn diffs(&self) -> IResult<Vec<ColumnDiff>> {
+		match (self.get(self.from), self.get(self.to)) {
+			(Some((_, from)), Some((_, to))) => {
+				diff::diff_columns(&to.columns, &from.columns, to.r#type)
+			}
+			_ => Ok(Vec::new()),
+		}
+	}
+}
+
+pub fn view(app: &App) -> Container<'_, Message, Renderer> {
+	let snapshot_view = &app.snapshot_view;
+	let title = match &snapshot_view.table {
+		Some((conn_uuid, database, table)) => {
+			format!("{} / {} / {}", app.conn_label(conn_uuid).0, database, table)
+		}
+		None => t!("snapshot.select_table"),
+	};
+	let mut toolbar =
+		row![text(title).size(20).width(Length::Fill)].spacing(5).align_items(Alignment::Center);
+	if snapshot_view.table.is_some() {
+		toolbar = toolbar.push(
+			button(text(t!("snapshot.take")))
+				.style(theme::Button::Primary)
+				.on_press(Message::TakeSnapshot),
+		);
+	}
+
+	let snapshots = if snapshot_view.snapshots.is_empty() {
+		Column::new().push(text(t!("snapshot.empty")))
+	} else {
+		snapshot_view.snapshots.iter().fold(Column::new().spacing(5), |base, (snapshot, table)| {
+			let id = Some(snapshot.id);
+			base.push(
+				container(
+					Row::new()
+						.push(
+							text(format!("#{} {}", snapshot.id, snapshot.taken_at))
+								.width(Length::Fill),
+						)
+						.push(text(t!("snapshot.columns", count = table.columns.len())).size(14))
+						.push(
+							button(text(t!("snapshot.from")))
+								.style(button_style(snapshot_view.from == id))
+								.on_press(Message::SelectSnapshotFrom(snapshot.id)),
+						)
+						.push(
+							button(text(t!("snapshot.to")))
+								.style(button_style(snapshot_view.to == id))
+								.on_press(Message::SelectSnapshotTo(snapshot.id)),
+						)
+						.align_items(Alignment::Center)
+						.spacing(5),
+				)
+				.width(Length::Fill)
+				.padding(5)
+				.style(border_style()),
+			)
+		})
+	};
+
+	container(
+		column![toolbar, scrollable(column![snapshots, comparison(snapshot_view)].spacing(10))]
+			.spacing(10),
+	)
+	.width(Length::Fill)
+	.height(Length::Fill)
+}
+
+/// Newer snapshot on the left, as the source the older one would be brought to.
+fn comparison(snapshot_view: &SnapshotView) -> Column<'_, Message, Renderer> {
+	let (Some((from, from_table)), Some((to, to_table))) =
+		(snapshot_view.get(snapshot_view.from), snapshot_view.get(snapshot_view.to))
+	else {
+		return Column::new();
+	};
+
+	let diffs = match snapshot_view.diffs() {
+		Ok(diffs) => diffs,
+		Err(e) => return Column::new().push(text(e.localized())),
+	};
+	let count = |f: fn(&ColumnDiff) -> bool| diffs.iter().filter(|diff| f(diff)).count();
+	let summary = if diffs.is_empty() {
+		t!("snapshot.identical")
+	} else {
+		t!(
+			"snapshot.changes",
+			added = count(|diff| matches!(diff, ColumnDiff::Added(_))),
+			removed = count(|diff| matches!(diff, ColumnDiff::Removed(_))),
+			modified = count(|diff| matches!(diff, ColumnDiff::Modified { .. }))
+		)
+	};
+
+	column![
+		text(summary),
+		row![
+			text(format!("#{} {}", to.id, to.taken_at)).width(Length::Fill),
+			text(format!("#{} {}", from.id, from.taken_at)).width(Length::Fill),
+		]
+		.spacing(10),
+		compare::diff_rows(
+			&to_table.columns,
+			to_table.r#type,
+			&from_table.columns,
+			to_table.r#type
+		),
+	]
+	.spacing(10)
+}
@@ -9,9 +9,9 @@ pub fn border_style() -> theme::Container {
 	impl container::StyleSheet for Style {
 		type Style = Theme;
 
-		fn appearance(&self, _: &Self::Style) -> container::Appearance {
+		fn appearance(&self, theme: &Self::Style) -> container::Appearance {
 			container::Appearance {
-				border_color: Color::BLACK,
+				border_color: theme.extended_palette().background.strong.color,
 				border_width: 1.0,
 				..Default::default()
 			}
@@ -21,6 +21,47 @@ pub fn border_style() -> theme::Container {
 	Container::Custom(Box::new(Style))
 }
 
+/// Status of an aligned row in the comparison view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+	Same,
+	/// Column only exists in source.
+	Added,
+	/// Column only exists in target.
+	Missing,
+	/// Column exists on both sides with different types.
+	Mismatch,
+}
+
+pub fn diff_style(status: DiffStatus) -> theme::Container {
+	impl container::StyleSheet for DiffStatus {
+		type Style = Theme;
+
+		fn appearance(&self, theme: &Self::Style) -> container::Appearance {
+			let palette = theme.extended_palette();
+			let background = match self {
+				DiffStatus::Same => return Default::default(),
+				DiffStatus::Added => palette.success.weak,
+				DiffStatus::Missing => palette.danger.weak,
+				DiffStatus::Mismatch => palette.primary.weak,
+			};
+
+			container::Appearance {
+				background: background.color.into(),
+				text_color: background.text.into(),
+				..Default::default()
+			}
+		}
+	}
+
+	Container::Custom(Box::new(status))
+}
+
+/// Colour of secondary text such as placeholders and loading hints.
+pub fn muted_color(theme: &Theme) -> Color {
+	theme.extended_palette().background.strong.color
+}
+
 pub fn button_style(active: bool) -> theme::Button {
 	if active {
 		theme::Button::Primary
@@ -0,0 +1,253 @@
+use iced::{
+	theme,
+	widget::{button, row, text, Row},
+	Alignment, Renderer,
+};
+
+use crate::{
+	i18n::t,
+	mapping::{column::ColumnSpec, database::DbType, table::Table},
+};
+
+use super::{compare::CompareTarget, App, Message};
+
+/// A table opened in the workspace, each tab keeps its own selection, target dialect, comparison
+/// and DDL preview.
+#[derive(Debug, Default)]
+pub struct Tab {
+	/// Stable across closing other tabs, background requests answer to it.
+	pub id: usize,
+	pub selected_conn: Option<String>,
+	pub selected_db: Option<String>,
+	pub selected_table: Option<String>,
+	pub selected_db_type: Option<DbType>,
+	pub origin_table_schema: Vec<ColumnSpec>,
+	/// Dialect of the selected table, resolved by the client along with its schema.
+	pub dialect: Option<DbType>,
+	pub loading_schema: bool,
+	pub show_compare: bool,
+	pub compare: CompareTarget,
+	/// Table parsed from a pasted DDL, such a tab has no connection.
+	pub pasted: Option<Table>,
+}
+
+impl Tab {
+	fn new(id: usize) -> Self {
+		Tab { id, ..Default::default() }
+	}
+
+	pub fn shows(&self, conn: &str, database: &str, table: &str) -> bool {
+		self.selected_conn.as_deref() == Some(conn)
+			&& self.selected_db.as_deref() == Some(database)
+			&& self.selected_table.as_deref() == Some(table)
+	}
+
+	/// Show another table, discarding everything related to the previous one.
+	pub fn open(&mut self, conn: String, database: String, table: String) {
+		*self = Tab {
+			id: self.id,
+			selected_conn: Some(conn),
+			selected_db: Some(database),
+			selected_table: Some(table),
+			..Default::default()
+		};
+	}
+
+	/// Show a table parsed from a pasted DDL.
+	pub fn paste(&mut self, table: Table) {
+		*self = Tab {
+			id: self.id,
+			selected_db: Some(table.database.clone()),
+			selected_table: Some(table.name.clone()),
+			origin_table_schema: table.columns.clone(),
+			pasted: Some(table),
+			..Default::default()
+		};
+	}
+
+	pub fn title(&self) -> String {
+		match (&self.selected_db, &self.selected_table) {
+			(Some(database), Some(table)) if !database.is_empty() => {
+				format!("{}.{}", database, table)
+			}
+			(_, Some(table)) => table.clone(),
+			_ => t!("tab.new"),
+		}
+	}
+}
+
+/// Open tabs, there is always at least one.
+#[derive(Debug)]
+pub struct Workspace {
+	pub tabs: Vec<Tab>,
+	pub active: usize,
+	next_id: usize,
+}
+
+impl Default for Workspace {
+	fn default() -> Self {
+		Workspace { tabs: vec![Tab::new(0)], active: 0, next_id: 1 }
+	}
+}
+
+impl Workspace {
+	pub fn active(&self) -> &Tab {
+		&self.tabs[self.active]
+	}
+
+	pub fn active_mut(&mut self) -> &mut Tab {
+		&mut self.tabs[self.active]
+	}
+
+	pub fn get_mut(&mut self, id: usize) -> Option<&mut Tab> {
+		self.tabs.iter_mut().find(|tab| tab.id == id)
+	}
+
+	/// Append an empty tab and make it active.
+	pub fn open(&mut self) -> &mut Tab {
+		self.tabs.push(Tab::new(self.next_id));
+		self.next_id += 1;
+		self.active = self.tabs.len() - 1;
+		self.active_mut()
+	}
+
+	/// Tab for showing `table`: the one already showing it, the active one while it is empty,
+	/// otherwise a new one.
+	pub fn open_table(&mut self, conn: String, database: String, table: String) -> &mut Tab {
+		if let Some(index) = self.tabs.iter().position(|tab| tab.shows(&conn, &database, &table)) {
+			self.active = index;
+			return self.active_mut();
+		}
+
+		let tab = self.vacant();
+		tab.open(conn, database, table);
+		tab
+	}
+
+	/// Tab for showing a pasted table: the active one while it is empty, otherwise a new one.
+	pub fn open_pasted(&mut self, table: Table) -> &mut Tab {
+		let tab = self.vacant();
+		tab.paste(table);
+		tab
+	}
+
+	/// Forget a deleted connection, tabs showing its tables are emptied and comparisons with it
+	/// dropped.
+	pub fn forget_conn(&mut self, uuid: &str) {
+		for tab in &mut self.tabs {
+			if tab.selected_conn.as_deref() == Some(uuid) {
+				*tab = Tab::new(tab.id);
+			} else if tab.compare.conn.as_deref() == Some(uuid) {
+				tab.compare = CompareTarget::default();
+			}
+		}
+	}
+
+	fn vacant(&mut self) -> &mut Tab {
+		if self.active().selected_table.is_none() {
+			self.active_mut()
+		} else {
+			self.open()
+		}
+	}
+
+	pub fn activate(&mut self, index: usize) {
+		if index < self.tabs.len() {
+			self.active = index;
+		}
+	}
+
+	/// Activate the tab `step` places away, wrapping around.
+	pub fn cycle(&mut self, step: i32) {
+		self.active = (self.active as i32 + step).rem_euclid(self.tabs.len() as i32) as usize;
+	}
+
+	/// Closing the last tab leaves an empty one.
+	pub fn close(&mut self, index: usize) {
+		if index >= self.tabs.len() {
+			return;
+		}
+
+		self.tabs.remove(index);
+		if self.tabs.is_empty() {
+			self.open();
+		} else if self.active > index || self.active == self.tabs.len() {
+			self.active -= 1;
+		}
+	}
+}
+
+pub fn view(app: &App) -> Row<'_, Message, Renderer> {
+	app.workspace
+		.tabs
+		.iter()
+		.enumerate()
+		.fold(Row::new().spacing(5), |base, (index, tab)| {
+			let active = index == app.workspace.active;
+			let style = || if active { theme::Button::Primary } else { theme::Button::Secondary };
+			base.push(
+				row![
+					button(text(tab.title())).style(style()).on_press(Message::SelectTab(index)),
+					button(text("x")).style(style()).on_press(Message::CloseTab(index)),
+				]
+				.spacing(1),
+			)
+		})
+		.push(button(text("+")).style(theme::Button::Text).on_press(Message::NewTab))
+		.padding(5)
+		.align_items(Alignment::Center)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Workspace;
+	use crate::mapping::table::Table;
+
+	#[test]
+	fn test_workspace() {
+		let mut workspace = Workspace::default();
+		workspace.open_table("a".into(), "db".into(), "t1".into());
+		assert_eq!(workspace.tabs.len(), 1);
+
+		workspace.open_table("a".into(), "db".into(), "t2".into());
+		workspace.open_table("a".into(), "db".into(), "t3".into());
+		assert_eq!((workspace.tabs.len(), workspace.active), (3, 2));
+
+		workspace.open_table("a".into(), "db".into(), "t1".into());
+		assert_eq!(workspace.active, 0);
+
+		workspace.cycle(-1);
+		assert_eq!(workspace.active().selected_table.as_deref(), Some("t3"));
+
+		workspace.close(0);
+		assert_eq!(workspace.active().selected_table.as_deref(), Some("t3"));
+		workspace.close(1);
+		assert_eq!(workspace.active().selected_table.as_deref(), Some("t2"));
+		workspace.close(0);
+		assert_eq!(workspace.tabs.len(), 1);
+		assert!(workspace.active().selected_table.is_none());
+		assert!(workspace.get_mut(0).is_none());
+
+		workspace.open_pasted(Table { name: "t4".into(), ..Default::default() });
+		workspace.open_pasted(Table { name: "t5".into(), ..Default::default() });
+		assert_eq!(workspace.tabs.len(), 2);
+		assert_eq!(workspace.active().title(), "t5");
+		assert!(workspace.active().selected_conn.is_none());
+	}
+
+	#[test]
+	fn test_forget_conn() {
+		let mut workspace = Workspace::default();
+		workspace.open_table("a".into(), "db".into(), "t1".into());
+		workspace.open_table("b".into(), "db".into(), "t2".into()).compare.conn = Some("a".into());
+		workspace.open_table("b".into(), "db".into(), "t3".into()).compare.conn = Some("c".into());
+
+		workspace.forget_conn("a");
+		assert_eq!(workspace.tabs.len(), 3);
+		assert!(workspace.tabs[0].selected_table.is_none());
+		assert_eq!(workspace.tabs[0].id, 0);
+		assert_eq!(workspace.tabs[1].selected_table.as_deref(), Some("t2"));
+		assert!(workspace.tabs[1].compare.conn.is_none());
+		assert_eq!(workspace.tabs[2].compare.conn.as_deref(), Some("c"));
+	}
+}
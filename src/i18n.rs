@@ -0,0 +1,118 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use once_cell::sync::Lazy;
+
+use crate::conf::{self, Language};
+
+/// Catalogues bundled into the binary, keyed by `section.name`.
+static CATALOGUES: Lazy<HashMap<Language, HashMap<String, String>>> = Lazy::new(|| {
+	Language::ALL.iter().map(|language| (*language, parse_catalogue(source(*language)))).collect()
+});
+
+/// Language chosen through [`set_language`], the one of the settings file until then.
+static LANGUAGE: RwLock<Option<Language>> = RwLock::new(None);
+
+/// Translate `key` into the current [`language`], `name = value` pairs fill in the `{name}`
+/// placeholders of the message.
+macro_rules! t {
+	($key:expr) => {
+		$crate::i18n::tr($key, &[])
+	};
+	($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+		$crate::i18n::tr($key, &[$((stringify!($name), $value.to_string())),+])
+	};
+}
+
+pub(crate) use t;
+
+pub fn tr(key: &str, args: &[(&str, String)]) -> String {
+	translate(language(), key, args)
+}
+
+/// Language the messages are translated into.
+pub fn language() -> Language {
+	LANGUAGE.read().unwrap().unwrap_or_else(conf::language)
+}
+
+/// Translate into `language` from now on, used when the settings come from another store.
+pub fn set_language(language: Language) {
+	*LANGUAGE.write().unwrap() = Some(language);
+}
+
+/// Falls back to English, then to the key itself when a message is missing.
+pub fn translate(language: Language, key: &str, args: &[(&str, String)]) -> String {
+	let message = CATALOGUES[&language]
+		.get(key)
+		.or_else(|| CATALOGUES[&Language::English].get(key))
+		.map_or(key, String::as_str);
+
+	args.iter().fold(message.to_owned(), |message, (name, value)| {
+		message.replace(&format!("{{{}}}", name), value)
+	})
+}
+
+fn source(language: Language) -> &'static str {
+	match language {
+		Language::English => include_str!("../locales/en.toml"),
+		Language::SimplifiedChinese => include_str!("../locales/zh_CN.toml"),
+	}
+}
+
+fn parse_catalogue(source: &str) -> HashMap<String, String> {
+	fn flatten(prefix: &str, table: toml::Table, messages: &mut HashMap<String, String>) {
+		for (name, value) in table {
+			let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+			match value {
+				toml::Value::Table(table) => flatten(&key, table, messages),
+				toml::Value::String(message) => {
+					messages.insert(key, message);
+				}
+				_ => {}
+			}
+		}
+	}
+
+	let mut messages = HashMap::new();
+	flatten(
+		"",
+		toml::from_str(source).expect("Bundled catalogue must be valid toml"),
+		&mut messages,
+	);
+	messages
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::{parse_catalogue, source, translate};
+	use crate::conf::Language;
+
+	#[test]
+	fn test_catalogues_complete() {
+		let english = parse_catalogue(source(Language::English));
+		for language in Language::ALL {
+			let catalogue = parse_catalogue(source(*language));
+			assert_eq!(
+				catalogue.keys().collect::<HashSet<_>>(),
+				english.keys().collect::<HashSet<_>>(),
+				"{:?}",
+				language
+			);
+		}
+	}
+
+	#[test]
+	fn test_translate() {
+		let args = [("name", "local".to_owned())];
+		assert_eq!(
+			translate(Language::English, "palette.edit_conn", &args),
+			"Edit connection local"
+		);
+		assert_eq!(
+			translate(Language::SimplifiedChinese, "palette.edit_conn", &args),
+			"编辑连接 local"
+		);
+		assert_eq!(translate(Language::SimplifiedChinese, "missing.key", &[]), "missing.key");
+	}
+}
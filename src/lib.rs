@@ -0,0 +1,21 @@
+//! Map and sync table schemas between MySQL, ClickHouse and other databases.
+//!
+//! The type mapping engine lives in [`mapping`], [`mapping::parse_type`] and
+//! [`mapping::convert_type`] are the entry points for services that embed it, and
+//! [`mapping::ddl_parser`] reads tables from `CREATE TABLE` statements. Embedders can turn off
+//! the default `cli` and `gui` features.
+
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod conf;
+pub mod conn;
+pub mod error;
+#[cfg(feature = "gui")]
+pub mod gui;
+mod i18n;
+pub mod mapping;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod spec;
+pub mod store;
+pub mod util;
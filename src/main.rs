@@ -1,16 +1,26 @@
-use gui::App;
-use iced::{Application, Settings};
-
-pub mod conf;
-pub mod conn;
-pub mod error;
-pub mod gui;
-pub mod mapping;
-pub mod store;
-pub mod util;
-
-pub fn main() -> error::IResult<()> {
-	conf::app_init();
+use std::process::ExitCode;
+
+use clap::Parser;
+use schema_syncr::{cli, conf, error, util};
+
+pub fn main() -> error::IResult<ExitCode> {
+	let cli = cli::Cli::parse();
+	if let Some(home) = cli.home {
+		util::set_app_root_dir(home);
+	}
+
+	conf::app_init()?;
+	match cli.command {
+		Some(command) => cli::run(command, cli.format),
+		None => run_gui().map(|_| ExitCode::SUCCESS),
+	}
+}
+
+#[cfg(feature = "gui")]
+fn run_gui() -> error::IResult<()> {
+	use iced::{Application, Settings};
+	use schema_syncr::gui::App;
+
 	App::run(Settings {
 		default_font: Some(include_bytes!("../fonts/fang_song.ttf")),
 		..Default::default()
@@ -18,6 +28,14 @@ pub fn main() -> error::IResult<()> {
 	Ok(())
 }
 
+#[cfg(not(feature = "gui"))]
+fn run_gui() -> error::IResult<()> {
+	use clap::CommandFactory;
+
+	cli::Cli::command().print_help()?;
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use std::{collections::HashMap, sync::Mutex};
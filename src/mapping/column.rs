@@ -3,9 +3,13 @@ use mysql::consts::ColumnType;
 
 use crate::error::{IError, IResult};
 
-use super::{database::DbType, type_parser_ch, type_parser_hbase, type_parser_mysql};
+use super::{
+	database::DbType, type_parser_ch, type_parser_hbase, type_parser_internal, type_parser_mysql,
+};
 
+/// JSON shape: `{"name": "id", "type": <DataType>, "comment": ""}`.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnSpec {
 	pub name: String,
 	pub r#type: DataType,
@@ -23,7 +27,16 @@ impl ColumnSpec {
 	}
 }
 
+/// JSON shape: `{"kind": "<snake_case variant>", "args": <fields>}`, `args` is omitted for unit
+/// variants, e.g. `{"kind": "int", "args": {"size": 8, "unsigned": false}}`, `{"kind": "bool"}`,
+/// `{"kind": "nullable", "args": {"kind": "string", "args": null}}` and
+/// `{"kind": "tuple", "args": [["name", {"kind": "string", "args": null}]]}`.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(
+	feature = "json",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "kind", content = "args", rename_all = "snake_case")
+)]
 pub enum DataType {
 	Int {
 		size: usize,
@@ -35,7 +48,13 @@ pub enum DataType {
 		scale: u8,
 	},
 	Bool,
+	/// Bit field of more than one bit, a single bit is a `Bool`.
+	Bit(usize),
 	String(Option<usize>),
+	/// Character large object holding up to the given number of bytes, see `LOB_SIZES`.
+	Text(usize),
+	/// Binary large object holding up to the given number of bytes, see `LOB_SIZES`.
+	Blob(usize),
 	Uuid,
 	Date,
 	Time,
@@ -59,11 +78,14 @@ pub enum DataType {
 impl DataType {
 	pub fn parse<T: Any + 'static>(any_type: T, db_type: DbType) -> IResult<DataType> {
 		match db_type {
-			DbType::MySQL => type_parser_mysql::parse(
-				*(&any_type as &dyn Any).downcast_ref::<ColumnType>().ok_or(
-					IError::PromptError("Can't downcast type value to ColumnType".to_owned()),
-				)?,
-			),
+			DbType::MySQL => match (&any_type as &dyn Any).downcast_ref::<String>() {
+				Some(type_str) => type_parser_mysql::parse_str(type_str),
+				None => type_parser_mysql::parse(
+					*(&any_type as &dyn Any).downcast_ref::<ColumnType>().ok_or(
+						IError::PromptError("Can't downcast type value to ColumnType".to_owned()),
+					)?,
+				),
+			},
 			DbType::ClickHouse => type_parser_ch::parse(
 				&(&any_type as &dyn Any)
 					.downcast_ref::<String>()
@@ -74,6 +96,11 @@ impl DataType {
 					.downcast_ref::<String>()
 					.ok_or(IError::PromptError("Can't downcast type value to String".to_owned()))?[..],
 			),
+			DbType::Internal => type_parser_internal::parse(
+				&(&any_type as &dyn Any)
+					.downcast_ref::<String>()
+					.ok_or(IError::PromptError("Can't downcast type value to String".to_owned()))?[..],
+			),
 			_ => Ok(Default::default()),
 		}
 	}
@@ -83,8 +110,8 @@ impl DataType {
 			DbType::MySQL => self.to_mysql_type(),
 			DbType::ClickHouse => self.to_ch_type(),
 			DbType::HBase => "".to_owned(),
-			DbType::Internal => format!("{:?}", self),
-			DbType::Unknown => panic!("Unsupported db type"),
+			DbType::Internal => type_parser_internal::print(self),
+			DbType::Fixture | DbType::Directory | DbType::Unknown => panic!("Unsupported db type"),
 		}
 	}
 
@@ -105,6 +132,9 @@ impl DataType {
 				format!("DECIMAL({}, {})", precision, scale)
 			}
 			DataType::Bool => "BOOL".to_owned(),
+			DataType::Bit(size) => format!("BIT({})", size),
+			DataType::Text(size) => format!("{}TEXT", lob_prefix(*size)),
+			DataType::Blob(size) => format!("{}BLOB", lob_prefix(*size)),
 			DataType::String(size_opt) => match size_opt {
 				&Some(size) if size > 65535 => "LONGTEXT".to_owned(),
 				&Some(size) => format!("VARCHAR({})", size),
@@ -126,7 +156,7 @@ impl DataType {
 	fn to_ch_type(&self) -> String {
 		match self {
 			DataType::Int { size, unsigned } => {
-				format!("{}{}", if *unsigned { "Uint" } else { "Int" }, size * 8)
+				format!("{}{}", if *unsigned { "UInt" } else { "Int" }, size * 8)
 			}
 			DataType::Float(size) => {
 				format!("Float{}", size * 8)
@@ -135,17 +165,21 @@ impl DataType {
 				format!("Decimal({},{})", precision, scale)
 			}
 			DataType::Bool => "Bool".to_owned(),
-			DataType::String(_) => "String".to_owned(),
+			DataType::Bit(size) => format!("UInt{}", size.next_power_of_two().max(8)),
+			DataType::String(_) | DataType::Text(_) | DataType::Blob(_) => "String".to_owned(),
 			DataType::Uuid => "UUID".to_owned(),
 			DataType::Date => "Date".to_owned(),
 			DataType::Time => "DateTime()".to_owned(),
+			DataType::DateTime { precision: 0, timezone: None } => "DateTime".to_owned(),
+			DataType::DateTime { precision: 0, timezone: Some(tz) } => {
+				format!("DateTime('{}')", tz)
+			}
 			DataType::DateTime { precision, timezone } => {
 				let mut buf = precision.to_string();
 				if let Some(tz) = timezone {
-					buf.push(',');
-					buf.push_str(tz);
+					buf.push_str(&format!(", '{}'", tz));
 				}
-				format!("Decimal64({})", buf)
+				format!("DateTime64({})", buf)
 			}
 			DataType::Array(sub_type) => {
 				format!("Array({})", Self::to_ch_type(sub_type))
@@ -166,7 +200,7 @@ impl DataType {
 						if tuple.0.len() > 0 {
 							format!("{} {}", &tuple.0, Self::to_ch_type(&tuple.1))
 						} else {
-							tuple.0.to_owned()
+							Self::to_ch_type(&tuple.1)
 						}
 					})
 					.collect::<Vec<_>>()
@@ -178,3 +212,16 @@ impl DataType {
 		}
 	}
 }
+
+/// Largest sizes of the MySQL `TINY`, plain, `MEDIUM` and `LONG` text and blob types.
+pub const LOB_SIZES: [usize; 4] = [255, 65535, 16777215, 4294967295];
+
+/// Prefix of the smallest MySQL text or blob type holding `size` bytes.
+fn lob_prefix(size: usize) -> &'static str {
+	match LOB_SIZES.iter().position(|max| size <= *max) {
+		Some(0) => "TINY",
+		Some(1) => "",
+		Some(2) => "MEDIUM",
+		_ => "LONG",
+	}
+}
@@ -7,6 +7,10 @@ pub enum DbType {
 	ClickHouse = 2,
 	HBase = 3,
 	Internal = 4,
+	/// Schemas served from a fixture file, see `conn::fixture`.
+	Fixture = 5,
+	/// Schemas kept in a directory of one file per table, see `conn::directory`.
+	Directory = 6,
 	#[default]
 	Unknown = 0,
 }
@@ -15,6 +19,13 @@ impl DbType {
 	pub const ALL: &'static [DbType; 4] =
 		&[DbType::Internal, DbType::MySQL, DbType::ClickHouse, DbType::HBase];
 
+	/// Types a connection can be created for.
+	pub const CONNECTABLE: &'static [DbType; 4] =
+		&[DbType::MySQL, DbType::ClickHouse, DbType::Fixture, DbType::Directory];
+
+	/// Dialects whose `CREATE TABLE` statements can be parsed, see `mapping::ddl_parser`.
+	pub const PARSABLE: &'static [DbType; 2] = &[DbType::MySQL, DbType::ClickHouse];
+
 	pub const DB_MYSQL: u8 = DbType::MySQL as u8;
 
 	pub const DB_CLICK_HOUSE: u8 = DbType::ClickHouse as u8;
@@ -22,6 +33,10 @@ impl DbType {
 	pub const DB_HBASE: u8 = DbType::HBase as u8;
 
 	pub const DB_INTERNAL: u8 = DbType::Internal as u8;
+
+	pub const DB_FIXTURE: u8 = DbType::Fixture as u8;
+
+	pub const DB_DIRECTORY: u8 = DbType::Directory as u8;
 }
 
 impl std::fmt::Display for DbType {
@@ -34,6 +49,8 @@ impl std::fmt::Display for DbType {
 				DbType::ClickHouse => "ClickHouse",
 				DbType::HBase => "HBase",
 				DbType::Internal => "Internal",
+				DbType::Fixture => "Fixture",
+				DbType::Directory => "Directory",
 				DbType::Unknown => "Unknown",
 			}
 		)
@@ -47,11 +64,26 @@ impl From<String> for DbType {
 			"ClickHouse" => Self::ClickHouse,
 			"HBase" => Self::HBase,
 			"Internal" => Self::Internal,
+			"Fixture" => Self::Fixture,
+			"Directory" => Self::Directory,
 			_ => Self::Unknown,
 		}
 	}
 }
 
+impl std::str::FromStr for DbType {
+	type Err = String;
+
+	/// Case-insensitive parsing, used by command line arguments.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		DbType::ALL
+			.iter()
+			.find(|db_type| db_type.to_string().eq_ignore_ascii_case(s))
+			.copied()
+			.ok_or(format!("Unknown db type: {}", s))
+	}
+}
+
 impl From<u8> for DbType {
 	fn from(value: u8) -> Self {
 		match value {
@@ -59,6 +91,8 @@ impl From<u8> for DbType {
 			Self::DB_CLICK_HOUSE => Self::ClickHouse,
 			Self::DB_HBASE => Self::HBase,
 			Self::DB_INTERNAL => Self::Internal,
+			Self::DB_FIXTURE => Self::Fixture,
+			Self::DB_DIRECTORY => Self::Directory,
 			_ => panic!("Unknown enum value"),
 		}
 	}
@@ -0,0 +1,777 @@
+//! Read `CREATE TABLE` statements of MySQL and ClickHouse into `Table`, e.g. the output of
+//! `mysqldump --no-data` or `SHOW CREATE TABLE`, so that a DDL dump can be converted without a
+//! connection. Column types go through the type parsers of the dialect. The column changes of
+//! `ALTER TABLE` statements are read too, so that plans can be applied to tables kept in files.
+
+use crate::error::{IError, IResult};
+
+use super::{
+	column::{ColumnSpec, DataType},
+	database::DbType,
+	table::Table,
+};
+
+/// Words starting what follows the type of a column.
+const MYSQL_COLUMN_ATTRIBUTES: [&'static str; 23] = [
+	"NOT",
+	"NULL",
+	"DEFAULT",
+	"AUTO_INCREMENT",
+	"COMMENT",
+	"PRIMARY",
+	"UNIQUE",
+	"KEY",
+	"CHARACTER",
+	"CHARSET",
+	"COLLATE",
+	"ON",
+	"GENERATED",
+	"AS",
+	"REFERENCES",
+	"CHECK",
+	"VISIBLE",
+	"INVISIBLE",
+	"COLUMN_FORMAT",
+	"STORAGE",
+	"SRID",
+	"AFTER",
+	"FIRST",
+];
+
+const CH_COLUMN_ATTRIBUTES: [&'static str; 13] = [
+	"NULL",
+	"NOT",
+	"DEFAULT",
+	"MATERIALIZED",
+	"EPHEMERAL",
+	"ALIAS",
+	"CODEC",
+	"TTL",
+	"COMMENT",
+	"PRIMARY",
+	"SETTINGS",
+	"AFTER",
+	"FIRST",
+];
+
+/// Words starting an index, a key or a constraint.
+const MYSQL_INDEXES: [&'static str; 9] =
+	["PRIMARY", "KEY", "INDEX", "UNIQUE", "FULLTEXT", "SPATIAL", "CONSTRAINT", "FOREIGN", "CHECK"];
+
+const CH_INDEXES: [&'static str; 4] = ["PRIMARY", "INDEX", "PROJECTION", "CONSTRAINT"];
+
+/// Clauses following the columns of a ClickHouse table.
+const CH_CLAUSES: [&'static str; 8] =
+	["ENGINE", "PARTITION", "PRIMARY", "ORDER", "SAMPLE", "TTL", "SETTINGS", "COMMENT"];
+
+/// A statement read by `parse`.
+#[derive(Debug, Clone)]
+pub enum Statement {
+	Create(Table),
+	Alter(AlterTable),
+}
+
+/// Column changes of an `ALTER TABLE` statement, such as those of `diff::alter_statements`.
+#[derive(Debug, Clone)]
+pub struct AlterTable {
+	pub database: String,
+	pub name: String,
+	pub changes: Vec<ColumnChange>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ColumnChange {
+	Add(ColumnSpec),
+	Modify(ColumnSpec),
+	Drop(String),
+}
+
+impl ColumnChange {
+	pub fn name(&self) -> &str {
+		match self {
+			ColumnChange::Add(column) | ColumnChange::Modify(column) => &column.name,
+			ColumnChange::Drop(name) => name,
+		}
+	}
+}
+
+impl AlterTable {
+	/// Apply the changes to `table` in order, the way the database of `table` would.
+	pub fn apply(&self, table: &mut Table) -> IResult<()> {
+		for change in &self.changes {
+			let name = change.name();
+			let position = table.columns.iter().position(|column| column.name == name);
+			match (change, position) {
+				(ColumnChange::Add(column), None) => table.columns.push(column.clone()),
+				(ColumnChange::Modify(column), Some(idx)) => {
+					// ClickHouse keeps the comment of a modified column, MySQL redefines it.
+					let comment = match table.r#type {
+						DbType::ClickHouse if column.comment.is_empty() => {
+							table.columns[idx].comment.clone()
+						}
+						_ => column.comment.clone(),
+					};
+					table.columns[idx] = ColumnSpec { comment, ..column.clone() };
+				}
+				(ColumnChange::Drop(_), Some(idx)) => {
+					table.columns.remove(idx);
+					table.primary_keys.retain(|key| key != name);
+					table.order_by.retain(|key| key != name);
+				}
+				(ColumnChange::Add(_), Some(_)) => {
+					return Err(IError::PromptError(format!(
+						"Column {} already exists in {}",
+						name, table.name
+					)))
+				}
+				(_, None) => {
+					return Err(IError::NotFoundError(format!("column {}.{}", table.name, name)))
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Parse the `CREATE TABLE` and `ALTER TABLE` statements in `sql`, written in the dialect of
+/// `db_type`. Other statements are skipped, except `USE db` which sets the database of the
+/// tables that follow. Only column changes can be read from `ALTER TABLE`.
+pub fn parse(sql: &str, db_type: DbType) -> IResult<Vec<Statement>> {
+	statements(sql, db_type, true)
+}
+
+/// Parse the `CREATE TABLE` statements in `sql`, see `parse`.
+pub fn parse_create_tables(sql: &str, db_type: DbType) -> IResult<Vec<Table>> {
+	Ok(statements(sql, db_type, false)?
+		.into_iter()
+		.filter_map(|statement| match statement {
+			Statement::Create(table) => Some(table),
+			Statement::Alter(_) => None,
+		})
+		.collect())
+}
+
+fn statements(sql: &str, db_type: DbType, with_alters: bool) -> IResult<Vec<Statement>> {
+	if !DbType::PARSABLE.contains(&db_type) {
+		return Err(IError::PromptError(format!("Unsupported ddl dialect: {}", db_type)));
+	}
+
+	let parser = Parser { sql, db_type };
+	let tokens = tokenize(sql)?;
+	let mut database = String::new();
+	let mut statements = Vec::new();
+	for statement in tokens.split(|token| token.is_punct(';')) {
+		match statement {
+			[first, name, ..] if first.is_word("USE") => database = name.value(),
+			[first, ..] if first.is_word("CREATE") => {
+				if let Some(table) = parser.create_table(statement, &database)? {
+					statements.push(Statement::Create(table));
+				}
+			}
+			[first, ..] if with_alters && first.is_word("ALTER") => {
+				if let Some(alter) = parser.alter_table(statement, &database)? {
+					statements.push(Statement::Alter(alter));
+				}
+			}
+			_ => {}
+		}
+	}
+
+	Ok(statements)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+	/// Keywords, unquoted names and numbers.
+	Word,
+	/// Names quoted with backticks or double quotes.
+	Quoted,
+	Str,
+	Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+	kind: Kind,
+	text: &'a str,
+	/// Byte range in the parsed sql.
+	start: usize,
+	end: usize,
+}
+
+impl Token<'_> {
+	fn is_word(&self, word: &str) -> bool {
+		self.kind == Kind::Word && self.text.eq_ignore_ascii_case(word)
+	}
+
+	fn is_any_word(&self, words: &[&str]) -> bool {
+		words.iter().any(|word| self.is_word(word))
+	}
+
+	fn is_punct(&self, punct: char) -> bool {
+		self.kind == Kind::Punct && self.text.starts_with(punct)
+	}
+
+	/// Text of the token with quotes removed and escapes resolved.
+	fn value(&self) -> String {
+		match self.kind {
+			Kind::Quoted | Kind::Str => {
+				let quote = self.text.chars().next().unwrap_or('\'');
+				unescape(&self.text[1..(self.text.len() - 1)], quote)
+			}
+			Kind::Word | Kind::Punct => self.text.to_owned(),
+		}
+	}
+}
+
+struct Parser<'a> {
+	sql: &'a str,
+	db_type: DbType,
+}
+
+impl Parser<'_> {
+	/// `None` for `CREATE` statements of anything else, or of a table copied from another one.
+	fn create_table(&self, tokens: &[Token], database: &str) -> IResult<Option<Table>> {
+		let word_at = |pos: usize, words: &[&str]| {
+			tokens.get(pos).map_or(false, |token: &Token| token.is_any_word(words))
+		};
+		let mut pos = 1;
+		while word_at(pos, &["OR", "REPLACE", "TEMPORARY"]) {
+			pos += 1;
+		}
+		if !word_at(pos, &["TABLE"]) {
+			return Ok(None);
+		}
+		pos += 1;
+		if word_at(pos, &["IF"]) {
+			// IF NOT EXISTS
+			pos += 3;
+		}
+
+		let (database, name, pos) = table_name(tokens, pos, database)?;
+		let mut table = Table { database, name, r#type: self.db_type, ..Default::default() };
+		if !tokens.get(pos).map_or(false, |token| token.is_punct('(')) {
+			return Ok(None);
+		}
+
+		let (body, end) = group(&tokens[pos..])?;
+		let options = &tokens[(pos + end)..];
+		for definition in split_top_level(body) {
+			match definition {
+				[] => {}
+				[first, ..] if first.is_any_word(self.indexes()) => {
+					if let Some(pos) = position_words(definition, &["PRIMARY", "KEY"]) {
+						table.primary_keys = self.key_list(&definition[(pos + 2)..])?;
+					}
+				}
+				_ => {
+					let (column, primary) = self.column(&table.name, definition)?;
+					if primary {
+						table.primary_keys.push(column.name.clone());
+					}
+					table.columns.push(column);
+				}
+			}
+		}
+
+		match self.db_type {
+			DbType::ClickHouse => self.ch_clauses(options, &mut table)?,
+			_ => {
+				if let Some(pos) = position_words(options, &["ENGINE"]) {
+					table.engine = options[(pos + 1)..]
+						.iter()
+						.find(|token| !token.is_punct('='))
+						.map_or(String::new(), Token::value);
+				}
+			}
+		}
+
+		Ok(Some(table))
+	}
+
+	/// `None` for `ALTER` statements of anything else.
+	fn alter_table(&self, tokens: &[Token], database: &str) -> IResult<Option<AlterTable>> {
+		if !tokens.get(1).map_or(false, |token| token.is_word("TABLE")) {
+			return Ok(None);
+		}
+		let pos = if tokens.get(2).map_or(false, |token| token.is_word("IF")) { 4 } else { 2 };
+
+		let (database, name, pos) = table_name(tokens, pos, database)?;
+		let mut changes = Vec::new();
+		for action in split_top_level(&tokens[pos..]) {
+			let (verb, target) = match action {
+				[verb, rest @ ..] => (verb, skip_words(rest, &["COLUMN", "IF", "NOT", "EXISTS"])),
+				[] => continue,
+			};
+			let change = match target {
+				[first, ..]
+					if first.is_any_word(self.indexes()) && !action[1].is_word("COLUMN") =>
+				{
+					None
+				}
+				[] => None,
+				_ if verb.is_word("ADD") => Some(ColumnChange::Add(self.column(&name, target)?.0)),
+				_ if verb.is_word("MODIFY") => {
+					Some(ColumnChange::Modify(self.column(&name, target)?.0))
+				}
+				[column, ..] if verb.is_word("DROP") => Some(ColumnChange::Drop(column.value())),
+				_ => None,
+			};
+			changes.push(change.ok_or(IError::PromptError(format!(
+				"Unsupported ALTER TABLE action: {}",
+				self.raw(action)
+			)))?);
+		}
+
+		Ok(Some(AlterTable { database, name, changes }))
+	}
+
+	/// Words starting a definition which isn't a column.
+	fn indexes(&self) -> &'static [&'static str] {
+		if self.db_type == DbType::MySQL {
+			&MYSQL_INDEXES
+		} else {
+			&CH_INDEXES
+		}
+	}
+
+	/// The column in `definition` of `table`, and whether it is declared as the primary key.
+	fn column(&self, table: &str, definition: &[Token]) -> IResult<(ColumnSpec, bool)> {
+		let name = definition[0].value();
+		self.column_spec(name.clone(), definition)
+			.map_err(|e| IError::PromptError(format!("{}.{}: {}", table, name, e)))
+	}
+
+	fn column_spec(&self, name: String, definition: &[Token]) -> IResult<(ColumnSpec, bool)> {
+		let attributes = if self.db_type == DbType::MySQL {
+			&MYSQL_COLUMN_ATTRIBUTES[..]
+		} else {
+			&CH_COLUMN_ATTRIBUTES[..]
+		};
+		let type_end = top_level(definition)
+			.skip(1)
+			.find(|(_, token)| token.is_any_word(attributes))
+			.map_or(definition.len(), |(pos, _)| pos);
+		if type_end <= 1 {
+			return Err(IError::PromptError("missing column type".to_owned()));
+		}
+
+		let attributes = &definition[type_end..];
+		let comment = position_words(attributes, &["COMMENT"])
+			.and_then(|pos| attributes.get(pos + 1))
+			.map_or(String::new(), Token::value);
+		let primary = position_words(attributes, &["PRIMARY", "KEY"]).is_some();
+
+		let r#type = DataType::parse(normalize(&definition[1..type_end]), self.db_type)?;
+		Ok((ColumnSpec { name, r#type, comment }, primary))
+	}
+
+	/// Keys of the table go to their fields, the engine call to `Table::engine` and the remaining
+	/// clauses except the comment to `Table::clauses`.
+	fn ch_clauses(&self, tokens: &[Token], table: &mut Table) -> IResult<()> {
+		let starts = top_level(tokens)
+			.filter(|(_, token)| token.is_any_word(&CH_CLAUSES))
+			.map(|(pos, _)| pos)
+			.collect::<Vec<_>>();
+
+		for (idx, start) in starts.iter().enumerate() {
+			let clause = &tokens[*start..starts.get(idx + 1).copied().unwrap_or(tokens.len())];
+			match clause {
+				[first, rest @ ..] if first.is_word("ENGINE") => {
+					let rest = if rest.first().map_or(false, |token| token.is_punct('=')) {
+						&rest[1..]
+					} else {
+						rest
+					};
+					table.engine = self.raw(rest);
+				}
+				[first, _, rest @ ..] if first.is_word("PRIMARY") => {
+					table.primary_keys = self.key_list(rest)?
+				}
+				[first, _, rest @ ..] if first.is_word("ORDER") => {
+					table.order_by = self.key_list(rest)?
+				}
+				[first, ..] if first.is_word("COMMENT") => {}
+				_ => table.clauses.push(self.raw(clause)),
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Names in `(a, b)`, `tuple(a, b)` or `a`, expressions are kept as written.
+	fn key_list(&self, tokens: &[Token]) -> IResult<Vec<String>> {
+		let tokens = match tokens {
+			[first, ..] if first.is_punct('(') => group(tokens)?.0,
+			[first, second, ..] if first.is_word("tuple") && second.is_punct('(') => {
+				group(&tokens[1..])?.0
+			}
+			_ => tokens,
+		};
+
+		Ok(split_top_level(tokens)
+			.into_iter()
+			.filter(|element| !element.is_empty())
+			.map(|element| match element {
+				[name] => name.value(),
+				// MySQL keys may carry a prefix length or an order, e.g. `name`(10) DESC.
+				[name, ..] if self.db_type == DbType::MySQL => name.value(),
+				_ => self.raw(element),
+			})
+			.collect())
+	}
+
+	fn raw(&self, tokens: &[Token]) -> String {
+		match (tokens.first(), tokens.last()) {
+			(Some(first), Some(last)) => self.sql[first.start..last.end].to_owned(),
+			_ => String::new(),
+		}
+	}
+}
+
+fn tokenize(sql: &str) -> IResult<Vec<Token<'_>>> {
+	let bytes = sql.as_bytes();
+	let is_word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80;
+
+	let mut tokens = Vec::new();
+	let mut pos = 0;
+	while pos < bytes.len() {
+		let start = pos;
+		let kind = match bytes[pos] {
+			b if b.is_ascii_whitespace() => {
+				pos += 1;
+				continue;
+			}
+			b'#' => {
+				pos = sql[pos..].find('\n').map_or(bytes.len(), |idx| pos + idx);
+				continue;
+			}
+			b'-' if bytes.get(pos + 1) == Some(&b'-') => {
+				pos = sql[pos..].find('\n').map_or(bytes.len(), |idx| pos + idx);
+				continue;
+			}
+			// Also drops the conditional comments of mysqldump, e.g. `/*!40101 SET .. */`.
+			b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+				pos = sql[(pos + 2)..].find("*/").map_or(bytes.len(), |idx| pos + idx + 4);
+				continue;
+			}
+			quote @ (b'`' | b'"' | b'\'') => {
+				pos = closing_quote(bytes, pos, quote)?;
+				if quote == b'\'' {
+					Kind::Str
+				} else {
+					Kind::Quoted
+				}
+			}
+			b if is_word_byte(b) => {
+				while pos < bytes.len() && is_word_byte(bytes[pos]) {
+					pos += 1;
+				}
+				Kind::Word
+			}
+			_ => {
+				pos += 1;
+				Kind::Punct
+			}
+		};
+		tokens.push(Token { kind, text: &sql[start..pos], start, end: pos });
+	}
+
+	Ok(tokens)
+}
+
+/// Position after the quote closing the one at `start`.
+fn closing_quote(bytes: &[u8], start: usize, quote: u8) -> IResult<usize> {
+	let mut pos = start + 1;
+	while pos < bytes.len() {
+		match bytes[pos] {
+			b'\\' if quote != b'`' => pos += 2,
+			b if b == quote && bytes.get(pos + 1) == Some(&quote) => pos += 2,
+			b if b == quote => return Ok(pos + 1),
+			_ => pos += 1,
+		}
+	}
+
+	Err(IError::PromptError(format!("Unclosed quote at byte {}", start)))
+}
+
+/// Backslash escapes, except in backticks, and doubled quotes.
+fn unescape(quoted: &str, quote: char) -> String {
+	let mut value = String::with_capacity(quoted.len());
+	let mut chars = quoted.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' if quote != '`' => match chars.next() {
+				Some('n') => value.push('\n'),
+				Some('t') => value.push('\t'),
+				Some('r') => value.push('\r'),
+				Some('0') => value.push('\0'),
+				Some(escaped) => value.push(escaped),
+				None => {}
+			},
+			_ if c == quote && chars.peek() == Some(&quote) => {
+				chars.next();
+				value.push(c);
+			}
+			_ => value.push(c),
+		}
+	}
+	value
+}
+
+/// Database and name of the table named at `pos`, and the position after the name and its
+/// `ON CLUSTER` clause.
+fn table_name(tokens: &[Token], pos: usize, database: &str) -> IResult<(String, String, usize)> {
+	let name_at = |pos: usize| match tokens.get(pos) {
+		Some(token) if token.kind == Kind::Word || token.kind == Kind::Quoted => Ok(token.value()),
+		_ => Err(IError::PromptError("Missing table name".to_owned())),
+	};
+
+	let (database, name, pos) = if tokens.get(pos + 1).map_or(false, |token| token.is_punct('.')) {
+		(name_at(pos)?, name_at(pos + 2)?, pos + 3)
+	} else {
+		(database.to_owned(), name_at(pos)?, pos + 1)
+	};
+	match tokens.get(pos) {
+		Some(token) if token.is_word("ON") => Ok((database, name, pos + 3)),
+		_ => Ok((database, name, pos)),
+	}
+}
+
+fn skip_words<'t, 'a>(tokens: &'t [Token<'a>], words: &[&str]) -> &'t [Token<'a>] {
+	let skipped = tokens.iter().take_while(|token| token.is_any_word(words)).count();
+	&tokens[skipped..]
+}
+
+/// Tokens inside the parentheses opened by the first token, and the position after them.
+fn group<'t, 'a>(tokens: &'t [Token<'a>]) -> IResult<(&'t [Token<'a>], usize)> {
+	let mut depth = 0;
+	for (pos, token) in tokens.iter().enumerate() {
+		if token.is_punct('(') {
+			depth += 1;
+		} else if token.is_punct(')') {
+			depth -= 1;
+			if depth == 0 {
+				return Ok((&tokens[1..pos], pos + 1));
+			}
+		}
+	}
+
+	Err(IError::PromptError("Unbalanced parentheses".to_owned()))
+}
+
+/// Tokens outside any parentheses with their position.
+fn top_level<'t, 'a>(tokens: &'t [Token<'a>]) -> impl Iterator<Item = (usize, &'t Token<'a>)> {
+	let mut depth = 0;
+	tokens.iter().enumerate().filter(move |(_, token)| {
+		if token.is_punct('(') {
+			depth += 1;
+		} else if token.is_punct(')') {
+			depth -= 1;
+			return false;
+		}
+		depth == 0
+	})
+}
+
+fn split_top_level<'t, 'a>(tokens: &'t [Token<'a>]) -> Vec<&'t [Token<'a>]> {
+	let mut parts = Vec::new();
+	let mut start = 0;
+	for (pos, token) in top_level(tokens) {
+		if token.is_punct(',') {
+			parts.push(&tokens[start..pos]);
+			start = pos + 1;
+		}
+	}
+	parts.push(&tokens[start..]);
+	parts
+}
+
+/// Position of `words` following each other outside parentheses.
+fn position_words(tokens: &[Token], words: &[&str]) -> Option<usize> {
+	top_level(tokens).map(|(pos, _)| pos).find(|pos| {
+		words
+			.iter()
+			.enumerate()
+			.all(|(idx, word)| tokens.get(pos + idx).map_or(false, |token| token.is_word(word)))
+	})
+}
+
+/// Tokens joined the way the type parsers expect them, e.g. `Decimal(20,4)`, `int unsigned`.
+fn normalize(tokens: &[Token]) -> String {
+	let mut joined = String::new();
+	let mut previous = Kind::Punct;
+	for token in tokens {
+		if previous != Kind::Punct && token.kind != Kind::Punct {
+			joined.push(' ');
+		}
+		joined.push_str(token.text);
+		previous = token.kind;
+	}
+	joined
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse, parse_create_tables, Statement};
+	use crate::mapping::{database::DbType, table::Table};
+
+	const MYSQL_DUMP: &str = r#"
+-- MySQL dump 10.13
+/*!40101 SET NAMES utf8mb4 */;
+USE `shop`;
+
+DROP TABLE IF EXISTS `user`;
+CREATE TABLE `user` (
+  `id` bigint(20) unsigned NOT NULL AUTO_INCREMENT COMMENT 'primary key',
+  `name` varchar(64) CHARACTER SET utf8mb4 NOT NULL DEFAULT '' COMMENT 'user''s name; display',
+  `score` decimal(10,2) DEFAULT NULL,
+  `created_at` datetime(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3) ON UPDATE CURRENT_TIMESTAMP(3),
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_name` (`name`(32)),
+  KEY `idx_score` (`score`)
+) ENGINE=InnoDB AUTO_INCREMENT=3 DEFAULT CHARSET=utf8mb4 COMMENT='users';
+
+CREATE TABLE IF NOT EXISTS audit.event (at datetime, id int PRIMARY KEY);
+CREATE TABLE user_copy LIKE `user`;
+"#;
+
+	const CH_DDL: &str = r#"
+CREATE TABLE logs.hit ON CLUSTER main
+(
+    `id` UInt64 COMMENT 'it\'s the id',
+    `ua` LowCardinality(Nullable(String)) CODEC(ZSTD(1)),
+    `amount` Decimal(20, 4) DEFAULT 0,
+    `at` DateTime64(3, 'UTC'),
+    INDEX idx_ua ua TYPE bloom_filter GRANULARITY 4
+)
+ENGINE = ReplicatedMergeTree('/clickhouse/{shard}/hit', '{replica}')
+PARTITION BY toYYYYMM(at)
+ORDER BY (id, toDate(at))
+SETTINGS index_granularity = 8192
+COMMENT 'hits'
+"#;
+
+	#[test]
+	fn test_parse_mysql() {
+		let tables = parse_create_tables(MYSQL_DUMP, DbType::MySQL).unwrap();
+		assert_eq!(tables.len(), 2);
+
+		let user = &tables[0];
+		assert_eq!((user.database.as_str(), user.name.as_str()), ("shop", "user"));
+		assert_eq!((user.r#type, user.engine.as_str()), (DbType::MySQL, "InnoDB"));
+		assert_eq!(user.primary_keys, vec!["id"]);
+		let columns = user
+			.columns
+			.iter()
+			.map(|column| (column.name.as_str(), column.r#type.to_type(DbType::Internal)))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			columns,
+			vec![
+				("id", "uint64".to_owned()),
+				("name", "string(64)".to_owned()),
+				("score", "decimal(10,2)".to_owned()),
+				("created_at", "datetime(3)".to_owned()),
+			]
+		);
+		assert_eq!(user.columns[0].comment, "primary key");
+		assert_eq!(user.columns[1].comment, "user's name; display");
+
+		let event = &tables[1];
+		assert_eq!((event.database.as_str(), event.name.as_str()), ("audit", "event"));
+		assert_eq!(event.primary_keys, vec!["id"]);
+	}
+
+	#[test]
+	fn test_parse_clickhouse() {
+		let tables = parse_create_tables(CH_DDL, DbType::ClickHouse).unwrap();
+		assert_eq!(tables.len(), 1);
+
+		let hit = &tables[0];
+		assert_eq!((hit.database.as_str(), hit.name.as_str()), ("logs", "hit"));
+		assert_eq!(hit.columns.len(), 4);
+		assert_eq!(hit.columns[0].comment, "it's the id");
+		assert_eq!(
+			hit.columns[1].r#type.to_type(DbType::Internal),
+			"low_cardinality<nullable<string>>"
+		);
+		assert_eq!(hit.columns[2].r#type.to_type(DbType::Internal), "decimal(20,4)");
+		assert_eq!(hit.columns[3].r#type.to_type(DbType::Internal), "datetime(3, 'UTC')");
+		assert_eq!(hit.order_by, vec!["id", "toDate(at)"]);
+		assert_eq!(hit.engine, "ReplicatedMergeTree('/clickhouse/{shard}/hit', '{replica}')");
+		assert_eq!(
+			hit.clauses,
+			vec!["PARTITION BY toYYYYMM(at)", "SETTINGS index_granularity = 8192"]
+		);
+
+		let sql = hit.to_create_sql(DbType::ClickHouse).unwrap();
+		assert!(
+			sql.ends_with(
+				")\nENGINE = ReplicatedMergeTree('/clickhouse/{shard}/hit', '{replica}')\n\
+				ORDER BY (`id`, toDate(at))\nPARTITION BY toYYYYMM(at)\n\
+				SETTINGS index_granularity = 8192"
+			),
+			"{}",
+			sql
+		);
+		let reparsed = parse_create_tables(&sql, DbType::ClickHouse).unwrap().remove(0);
+		assert_eq!((reparsed.engine, reparsed.clauses), (hit.engine.clone(), hit.clauses.clone()));
+	}
+
+	fn alter(table: &mut Table, sql: &str) -> crate::error::IResult<()> {
+		for statement in parse(sql, table.r#type)? {
+			match statement {
+				Statement::Alter(alter) => alter.apply(table)?,
+				Statement::Create(_) => panic!("{}", sql),
+			}
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_alter() {
+		let mut user = parse_create_tables(MYSQL_DUMP, DbType::MySQL).unwrap().remove(0);
+		alter(
+			&mut user,
+			"ALTER TABLE `shop`.`user` ADD COLUMN `email` VARCHAR(128) COMMENT 'mail';
+			ALTER TABLE `shop`.`user` MODIFY COLUMN `id` BIGINT unsigned, DROP COLUMN `score`",
+		)
+		.unwrap();
+		let names = user.columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(names, vec!["id", "name", "created_at", "email"]);
+		assert_eq!(
+			(user.columns[0].comment.as_str(), user.columns[3].comment.as_str()),
+			("", "mail")
+		);
+
+		assert!(alter(&mut user, "ALTER TABLE user ADD `name` int").is_err());
+		assert!(alter(&mut user, "ALTER TABLE user DROP COLUMN `score`").is_err());
+		assert!(alter(&mut user, "ALTER TABLE user ADD INDEX idx_name (`name`)").is_err());
+		assert!(parse_create_tables("ALTER TABLE user ADD INDEX idx (a)", DbType::MySQL)
+			.unwrap()
+			.is_empty());
+
+		let mut hit = parse_create_tables(CH_DDL, DbType::ClickHouse).unwrap().remove(0);
+		alter(&mut hit, "ALTER TABLE logs.hit ON CLUSTER main MODIFY COLUMN `id` UInt32").unwrap();
+		assert_eq!(hit.columns[0].r#type.to_type(DbType::ClickHouse), "UInt32");
+		assert_eq!(hit.columns[0].comment, "it's the id");
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert!(parse_create_tables("CREATE TABLE t (a int", DbType::MySQL).is_err());
+		assert!(parse_create_tables("CREATE TABLE t (a 'x)", DbType::MySQL).is_err());
+		assert!(parse_create_tables("CREATE TABLE t (a)", DbType::MySQL).is_err());
+		assert!(parse_create_tables("CREATE TABLE t (a int)", DbType::HBase).is_err());
+		for column in ["a FixedString", "a Decimal(10)", "a Map(String)", "a Nullable", "a Strnig"]
+		{
+			let sql = format!("CREATE TABLE t ({}) ENGINE = Log", column);
+			assert!(parse_create_tables(&sql, DbType::ClickHouse).is_err(), "{}", sql);
+		}
+		assert!(parse_create_tables("SELECT 1; CREATE VIEW v AS SELECT 1", DbType::MySQL)
+			.unwrap()
+			.is_empty());
+	}
+}
@@ -0,0 +1,184 @@
+use crate::error::{IError, IResult};
+
+use super::{column::ColumnSpec, database::DbType, table::Table};
+
+/// Difference of a single column between a source and a target table.
+///
+/// JSON shape: `{"change": "added" | "removed", <ColumnSpec fields>}` or
+/// `{"change": "modified", "source": <ColumnSpec>, "target": <ColumnSpec>}`.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+	feature = "json",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(tag = "change", rename_all = "snake_case")
+)]
+pub enum ColumnDiff {
+	/// Column exists in source but is missing in target.
+	Added(ColumnSpec),
+	/// Column only exists in target.
+	Removed(ColumnSpec),
+	/// Column exists on both sides with a different type.
+	Modified { source: ColumnSpec, target: ColumnSpec },
+}
+
+impl ColumnDiff {
+	pub fn name(&self) -> &str {
+		match self {
+			ColumnDiff::Added(column) | ColumnDiff::Removed(column) => &column.name,
+			ColumnDiff::Modified { source, .. } => &source.name,
+		}
+	}
+}
+
+/// Compare columns by name, types are compared by their representation in `dialect`, which must
+/// be able to write every type.
+pub fn diff_columns(
+	source: &[ColumnSpec],
+	target: &[ColumnSpec],
+	dialect: DbType,
+) -> IResult<Vec<ColumnDiff>> {
+	if !matches!(dialect, DbType::MySQL | DbType::ClickHouse | DbType::Internal) {
+		return Err(IError::PromptError(format!("Unsupported diff dialect: {}", dialect)));
+	}
+
+	let mut diffs = Vec::new();
+	for source_column in source {
+		match target.iter().find(|column| column.name == source_column.name) {
+			Some(target_column) => {
+				if source_column.r#type.to_type(dialect) != target_column.r#type.to_type(dialect) {
+					diffs.push(ColumnDiff::Modified {
+						source: source_column.clone(),
+						target: target_column.clone(),
+					});
+				}
+			}
+			None => diffs.push(ColumnDiff::Added(source_column.clone())),
+		}
+	}
+
+	for target_column in target {
+		if !source.iter().any(|column| column.name == target_column.name) {
+			diffs.push(ColumnDiff::Removed(target_column.clone()));
+		}
+	}
+
+	Ok(diffs)
+}
+
+/// Generate `ALTER TABLE` statements which make target table consistent with source table.
+pub fn alter_statements(
+	database: &str,
+	table: &str,
+	diffs: &[ColumnDiff],
+	dialect: DbType,
+	drop_removed: bool,
+) -> IResult<Vec<String>> {
+	if !matches!(dialect, DbType::MySQL | DbType::ClickHouse) {
+		return Err(IError::PromptError(format!("Unsupported ddl dialect: {}", dialect)));
+	}
+
+	let statements = diffs
+		.iter()
+		.filter_map(|diff| {
+			let action = match diff {
+				ColumnDiff::Added(column) => {
+					format!("ADD COLUMN `{}` {}", column.name, column.r#type.to_type(dialect))
+				}
+				ColumnDiff::Modified { source, .. } => {
+					format!("MODIFY COLUMN `{}` {}", source.name, source.r#type.to_type(dialect))
+				}
+				ColumnDiff::Removed(column) if drop_removed => {
+					format!("DROP COLUMN `{}`", column.name)
+				}
+				ColumnDiff::Removed(_) => return None,
+			};
+			Some(format!("ALTER TABLE `{}`.`{}` {}", database, table, action))
+		})
+		.collect();
+
+	Ok(statements)
+}
+
+/// Diff `source` against `target` and generate the statements which make target consistent, a
+/// `CREATE TABLE` statement is generated if target has no columns yet. Fails if source has no
+/// columns, which is what a missing source table looks like.
+pub fn plan(
+	source: &Table,
+	target: &Table,
+	drop_removed: bool,
+) -> IResult<(Vec<ColumnDiff>, Vec<String>)> {
+	if source.columns.is_empty() {
+		return Err(IError::NotFoundError(format!(
+			"columns of source table {}.{}",
+			source.database, source.name
+		)));
+	}
+
+	let dialect = target.r#type;
+	let diffs = diff_columns(&source.columns, &target.columns, dialect)?;
+	let statements = if target.columns.is_empty() {
+		let table = Table {
+			name: target.name.clone(),
+			database: target.database.clone(),
+			..source.clone()
+		};
+		vec![table.to_create_sql(dialect)?]
+	} else {
+		alter_statements(&target.database, &target.name, &diffs, dialect, drop_removed)?
+	};
+
+	Ok((diffs, statements))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{alter_statements, diff_columns, plan, ColumnDiff};
+	use crate::mapping::{
+		column::{ColumnSpec, DataType},
+		database::DbType,
+		table::Table,
+	};
+
+	fn column(name: &str, r#type: DataType) -> ColumnSpec {
+		ColumnSpec { name: name.to_owned(), r#type, comment: "".to_owned() }
+	}
+
+	#[test]
+	fn test_diff_columns() {
+		let source = vec![
+			column("id", DataType::Int { size: 8, unsigned: false }),
+			column("name", DataType::String(None)),
+			column("age", DataType::Int { size: 2, unsigned: false }),
+		];
+		let target = vec![
+			column("id", DataType::Int { size: 8, unsigned: false }),
+			column("name", DataType::String(Some(16))),
+			column("age", DataType::Int { size: 1, unsigned: false }),
+			column("email", DataType::String(None)),
+		];
+
+		let diffs = diff_columns(&source, &target, DbType::ClickHouse).unwrap();
+		assert_eq!(diffs.len(), 2);
+		assert!(matches!(&diffs[0], ColumnDiff::Modified { source, .. } if source.name == "age"));
+		assert!(matches!(&diffs[1], ColumnDiff::Removed(column) if column.name == "email"));
+
+		let statements = alter_statements("db", "user", &diffs, DbType::ClickHouse, false).unwrap();
+		assert_eq!(statements, vec!["ALTER TABLE `db`.`user` MODIFY COLUMN `age` Int16"]);
+
+		assert_eq!(diff_columns(&source, &target, DbType::Internal).unwrap().len(), 3);
+		assert!(diff_columns(&source, &target, DbType::HBase).is_err());
+		assert!(diff_columns(&source, &target, DbType::Fixture).is_err());
+	}
+
+	#[test]
+	fn test_plan_without_source_columns() {
+		let table =
+			|columns| Table::new("db".to_owned(), "user".to_owned(), DbType::MySQL, columns);
+		let target = table(vec![column("id", DataType::Int { size: 8, unsigned: false })]);
+		assert!(plan(&table(vec![]), &target, true).is_err());
+		assert!(plan(&table(vec![]), &table(vec![]), false).is_err());
+
+		let (_, statements) = plan(&target, &table(vec![]), false).unwrap();
+		assert!(statements[0].starts_with("CREATE TABLE"), "{:?}", statements);
+	}
+}
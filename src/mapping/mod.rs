@@ -1,10 +1,35 @@
+use crate::error::{IError, IResult};
+
+pub use self::{
+	column::{ColumnSpec, DataType},
+	database::DbType,
+	table::Table,
+};
+
 pub mod column;
 pub mod database;
+pub mod ddl_parser;
+pub mod diff;
 pub mod table;
 mod type_parser_ch;
 mod type_parser_hbase;
+mod type_parser_internal;
 mod type_parser_mysql;
 
+/// Parse a column type written in the dialect of `db_type`.
+pub fn parse_type(type_str: &str, db_type: DbType) -> IResult<DataType> {
+	DataType::parse(type_str.to_owned(), db_type)
+}
+
+/// Convert a column type from the dialect of `from` to the dialect of `to`.
+pub fn convert_type(type_str: &str, from: DbType, to: DbType) -> IResult<String> {
+	if matches!(to, DbType::Unknown | DbType::Fixture | DbType::Directory) {
+		return Err(IError::PromptError(format!("Can't convert type to {}", to)));
+	}
+
+	Ok(parse_type(type_str, from)?.to_type(to))
+}
+
 fn is_balanced_brackets(s: &str) -> bool {
 	let mut stack = Vec::new();
 	for c in s.chars() {
@@ -21,12 +46,53 @@ fn is_balanced_brackets(s: &str) -> bool {
 	stack.is_empty()
 }
 
-trait FindSkip {
-	fn find_skip(&self, p: char, num: usize) -> Option<usize>;
-}
+#[cfg(test)]
+mod tests {
+	use super::{convert_type, DbType};
+
+	#[test]
+	fn test_convert_type() {
+		assert_eq!(
+			convert_type("bigint(20) unsigned", DbType::MySQL, DbType::ClickHouse).unwrap(),
+			"UInt64"
+		);
+		assert_eq!(
+			convert_type("Nullable(Decimal(20,4))", DbType::ClickHouse, DbType::MySQL).unwrap(),
+			"DECIMAL(20, 4)"
+		);
+		assert!(convert_type("String", DbType::ClickHouse, DbType::Unknown).is_err());
+		assert_eq!(
+			convert_type(
+				"Map(String, Array(Nullable(Int32)))",
+				DbType::ClickHouse,
+				DbType::Internal
+			)
+			.unwrap(),
+			"map<string, array<nullable<int32>>>"
+		);
+		assert_eq!(
+			convert_type("tuple<a: uint64, string>", DbType::Internal, DbType::ClickHouse).unwrap(),
+			"Tuple(a UInt64,String)"
+		);
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn test_json_shape() {
+		use super::{parse_type, ColumnSpec};
 
-impl FindSkip for str {
-	fn find_skip(&self, p: char, num: usize) -> Option<usize> {
-		self.chars().enumerate().filter(|&(_, c)| c == p).nth(num).map(|(idx, _)| idx)
+		let column = ColumnSpec {
+			name: "tags".to_owned(),
+			r#type: parse_type("Array(Nullable(Int8))", DbType::ClickHouse).unwrap(),
+			comment: "".to_owned(),
+		};
+		assert_eq!(
+			serde_json::to_string(&column).unwrap(),
+			r#"{"name":"tags","type":{"kind":"array","args":{"kind":"nullable","args":{"kind":"int","args":{"size":1,"unsigned":false}}}},"comment":""}"#
+		);
+		assert_eq!(
+			serde_json::to_string(&parse_type("Bool", DbType::ClickHouse).unwrap()).unwrap(),
+			r#"{"kind":"bool"}"#
+		);
 	}
 }
@@ -1,17 +1,186 @@
+use crate::error::{IError, IResult};
+
 use super::{column::ColumnSpec, database::DbType};
 
-pub struct Table<'a> {
+const DEFAULT_CH_ENGINE: &'static str = "MergeTree";
+
+/// JSON shape: `{"name": "user", "database": "db", "type": "MySQL", "columns": [<ColumnSpec>],
+/// "primary_keys": ["id"], "order_by": [], "engine": "", "clauses": ["SETTINGS ..."]}`, `clauses`
+/// is omitted when empty.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
 	pub name: String,
 	pub database: String,
 	pub r#type: DbType,
 	pub columns: Vec<ColumnSpec>,
-	pub primary_keys: Option<Vec<&'a ColumnSpec>>,
-	pub order_by: Option<Vec<&'a ColumnSpec>>,
+	pub primary_keys: Vec<String>,
+	pub order_by: Vec<String>,
+	/// Engine of a ClickHouse table such as `MergeTree`, or of a MySQL table such as `InnoDB`.
 	pub engine: String,
+	/// Clauses following the keys of a ClickHouse table as written, e.g. `PARTITION BY
+	/// toYYYYMM(at)`, `SETTINGS` is always emitted last.
+	#[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Vec::is_empty"))]
+	pub clauses: Vec<String>,
+}
+
+impl Table {
+	pub fn new(database: String, name: String, r#type: DbType, columns: Vec<ColumnSpec>) -> Table {
+		Table { name, database, r#type, columns, ..Default::default() }
+	}
+
+	/// Generate the `CREATE TABLE` statement of this table in the dialect of `db_type`.
+	pub fn to_create_sql(&self, db_type: DbType) -> IResult<String> {
+		match db_type {
+			DbType::MySQL => Ok(self.to_mysql_create_sql()),
+			DbType::ClickHouse => Ok(self.to_ch_create_sql()),
+			DbType::Internal => Ok(self.to_internal_create_sql()),
+			_ => Err(IError::PromptError(format!("Unsupported ddl dialect: {}", db_type))),
+		}
+	}
+
+	fn to_mysql_create_sql(&self) -> String {
+		let mut lines = self
+			.columns
+			.iter()
+			.map(|column| {
+				format!(
+					"  `{}` {}{}",
+					column.name,
+					column.r#type.to_type(DbType::MySQL),
+					comment_clause(&column.comment)
+				)
+			})
+			.collect::<Vec<_>>();
+		if !self.primary_keys.is_empty() {
+			lines.push(format!("  PRIMARY KEY ({})", quote_names(&self.primary_keys)));
+		}
+
+		format!("CREATE TABLE {} (\n{}\n)", self.quoted_name(), lines.join(",\n"))
+	}
+
+	fn to_ch_create_sql(&self) -> String {
+		let columns = self
+			.columns
+			.iter()
+			.map(|column| {
+				format!(
+					"    `{}` {}{}",
+					column.name,
+					column.r#type.to_type(DbType::ClickHouse),
+					comment_clause(&column.comment)
+				)
+			})
+			.collect::<Vec<_>>()
+			.join(",\n");
+
+		let engine = match self.r#type {
+			DbType::ClickHouse if !self.engine.is_empty() => &self.engine[..],
+			_ => DEFAULT_CH_ENGINE,
+		};
+		let order_by = if !self.order_by.is_empty() { &self.order_by } else { &self.primary_keys };
+		let mut sql =
+			format!("CREATE TABLE {}\n(\n{}\n)\nENGINE = {}", self.quoted_name(), columns, engine);
+		if !self.primary_keys.is_empty() {
+			sql.push_str(&format!("\nPRIMARY KEY ({})", quote_names(&self.primary_keys)));
+		}
+		if order_by.is_empty() {
+			sql.push_str("\nORDER BY tuple()");
+		} else {
+			sql.push_str(&format!("\nORDER BY ({})", quote_names(order_by)));
+		}
+		if self.r#type == DbType::ClickHouse {
+			let is_settings = |clause: &&String| clause.to_uppercase().starts_with("SETTINGS");
+			let (settings, others): (Vec<_>, Vec<_>) = self.clauses.iter().partition(is_settings);
+			for clause in others.into_iter().chain(settings) {
+				sql.push_str(&format!("\n{}", clause));
+			}
+		}
+
+		sql
+	}
+
+	fn to_internal_create_sql(&self) -> String {
+		let columns = self
+			.columns
+			.iter()
+			.map(|column| format!("  {} {}", column.name, column.r#type.to_type(DbType::Internal)))
+			.collect::<Vec<_>>()
+			.join(",\n");
+		if self.database.is_empty() {
+			format!("TABLE {} (\n{}\n)", self.name, columns)
+		} else {
+			format!("TABLE {}.{} (\n{}\n)", self.database, self.name, columns)
+		}
+	}
+
+	/// Tables parsed from a DDL without a database are named alone.
+	fn quoted_name(&self) -> String {
+		if self.database.is_empty() {
+			format!("`{}`", self.name)
+		} else {
+			format!("`{}`.`{}`", self.database, self.name)
+		}
+	}
+}
+
+/// Expressions such as `toDate(at)` in ClickHouse keys are left as they are.
+fn quote_names(names: &[String]) -> String {
+	names
+		.iter()
+		.map(|name| if name.contains('(') { name.clone() } else { format!("`{}`", name) })
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+fn comment_clause(comment: &str) -> String {
+	if comment.is_empty() {
+		"".to_owned()
+	} else {
+		format!(" COMMENT '{}'", comment.replace('\\', "\\\\").replace('\'', "\\'"))
+	}
 }
 
 #[cfg(test)]
 mod tests {
+	use super::Table;
+	use crate::mapping::{
+		column::{ColumnSpec, DataType},
+		database::DbType,
+	};
+
+	#[test]
+	fn test_to_create_sql() {
+		let mut table = Table::new(
+			"db".to_owned(),
+			"user".to_owned(),
+			DbType::MySQL,
+			vec![
+				ColumnSpec {
+					name: "id".to_owned(),
+					r#type: DataType::Int { size: 8, unsigned: true },
+					comment: "".to_owned(),
+				},
+				ColumnSpec {
+					name: "name".to_owned(),
+					r#type: DataType::String(Some(64)),
+					comment: "user's name".to_owned(),
+				},
+			],
+		);
+		table.primary_keys = vec!["id".to_owned()];
+
+		assert_eq!(
+			table.to_create_sql(DbType::MySQL).unwrap(),
+			"CREATE TABLE `db`.`user` (\n  `id` BIGINT unsigned,\n  `name` VARCHAR(64) COMMENT \
+			'user\\'s name',\n  PRIMARY KEY (`id`)\n)"
+		);
+		assert_eq!(
+			table.to_create_sql(DbType::ClickHouse).unwrap(),
+			"CREATE TABLE `db`.`user`\n(\n    `id` UInt64,\n    `name` String COMMENT \
+			'user\\'s name'\n)\nENGINE = MergeTree\nPRIMARY KEY (`id`)\nORDER BY (`id`)"
+		);
+	}
 
 	#[test]
 	fn test_ref_to_self_location() {
@@ -1,145 +1,206 @@
 use crate::error::{IError, IResult};
 
-use super::{column::DataType, is_balanced_brackets, FindSkip};
+use super::{column::DataType, is_balanced_brackets};
 
 pub fn parse<T: AsRef<str>>(type_str: T) -> IResult<DataType> {
-	Ok(match type_str.as_ref().trim() {
-		"Int8" => DataType::Int { size: 1, unsigned: false },
-		"Int16" => DataType::Int { size: 2, unsigned: false },
-		"Int32" => DataType::Int { size: 4, unsigned: false },
-		"Int64" => DataType::Int { size: 8, unsigned: false },
-		"Int128" => DataType::Int { size: 16, unsigned: false },
-		"Int256" => DataType::Int { size: 32, unsigned: false },
-		"UInt8" => DataType::Int { size: 1, unsigned: true },
-		"UInt16" => DataType::Int { size: 2, unsigned: true },
-		"UInt32" => DataType::Int { size: 4, unsigned: true },
-		"UInt64" => DataType::Int { size: 8, unsigned: true },
-		"UInt128" => DataType::Int { size: 16, unsigned: true },
-		"UInt256" => DataType::Int { size: 32, unsigned: true },
-		"Float32" => DataType::Float(4),
-		"Float64" => DataType::Float(8),
-		"Bool" => DataType::Bool,
-		"IPv4" => DataType::Int { size: 4, unsigned: true },
-		"IPv6" => DataType::String(Some(16)),
-		"String" => DataType::String(None),
-		s if s.starts_with("FixedString") => DataType::String(Some(s[12..(s.len() - 1)].parse()?)),
-		"UUID" => DataType::Uuid,
-		"Date" | "Date32" => DataType::Date,
-		s if s.starts_with("Decimal(") => {
-			let sub_str = &s[8..(s.len() - 1)];
-			let idx = sub_str.find(",").expect("Wrong decimal type!");
-			DataType::Decimal {
-				precision: sub_str[..idx].parse()?,
-				scale: sub_str[(idx + 1)..].parse()?,
+	let type_str = type_str.as_ref().trim();
+	let malformed = || IError::PromptError(format!("Malformed type: {}", type_str));
+	let (name, args) = match type_str.find('(') {
+		Some(idx) if type_str.ends_with(')') => {
+			let args = &type_str[(idx + 1)..(type_str.len() - 1)];
+			if !is_balanced_brackets(args) {
+				return Err(malformed());
 			}
+			(type_str[..idx].trim(), Some(split_args(args)))
 		}
-		s if s.starts_with("Decimal32") => {
-			let scale: u8 = s[10..(s.len() - 1)].parse()?;
-			let precision = 10 - (f64::log10(scale as f64)).ceil() as u8;
-			DataType::Decimal { precision, scale }
-		}
-		s if s.starts_with("Decimal64") => {
-			let scale: u8 = s[10..(s.len() - 1)].parse()?;
-			let precision = 19 - (f64::log10(scale as f64)).ceil() as u8;
-			DataType::Decimal { precision, scale }
-		}
-		s if s.starts_with("Decimal128") => {
-			let scale: u8 = s[11..(s.len() - 1)].parse()?;
-			let precision = 39 - (f64::log10(scale as f64)).ceil() as u8;
-			DataType::Decimal { precision, scale }
+		Some(_) => return Err(malformed()),
+		None => (type_str, None),
+	};
+	let single = || match args.as_deref() {
+		Some([arg]) if !arg.is_empty() => Ok(*arg),
+		_ => Err(malformed()),
+	};
+	let pair = || match args.as_deref() {
+		Some([first, second]) if !first.is_empty() && !second.is_empty() => Ok((*first, *second)),
+		_ => Err(malformed()),
+	};
+	// Precision of `Decimal32(S)` and its siblings, which only take the scale.
+	let decimal = |max_precision: u8| -> IResult<DataType> {
+		let scale: u8 = single()?.parse()?;
+		let precision = max_precision - (f64::log10(scale as f64)).ceil() as u8;
+		Ok(DataType::Decimal { precision, scale })
+	};
+
+	Ok(match (name, &args) {
+		("Int8", None) => DataType::Int { size: 1, unsigned: false },
+		("Int16", None) => DataType::Int { size: 2, unsigned: false },
+		("Int32", None) => DataType::Int { size: 4, unsigned: false },
+		("Int64", None) => DataType::Int { size: 8, unsigned: false },
+		("Int128", None) => DataType::Int { size: 16, unsigned: false },
+		("Int256", None) => DataType::Int { size: 32, unsigned: false },
+		("UInt8", None) => DataType::Int { size: 1, unsigned: true },
+		("UInt16", None) => DataType::Int { size: 2, unsigned: true },
+		("UInt32", None) => DataType::Int { size: 4, unsigned: true },
+		("UInt64", None) => DataType::Int { size: 8, unsigned: true },
+		("UInt128", None) => DataType::Int { size: 16, unsigned: true },
+		("UInt256", None) => DataType::Int { size: 32, unsigned: true },
+		("Float32", None) => DataType::Float(4),
+		("Float64", None) => DataType::Float(8),
+		("Bool", None) => DataType::Bool,
+		("IPv4", None) => DataType::Int { size: 4, unsigned: true },
+		("IPv6", None) => DataType::String(Some(16)),
+		("String", None) => DataType::String(None),
+		("FixedString", _) => DataType::String(Some(single()?.parse()?)),
+		("UUID", None) => DataType::Uuid,
+		("Date" | "Date32", None) => DataType::Date,
+		("Decimal", _) => {
+			let (precision, scale) = pair()?;
+			DataType::Decimal { precision: precision.parse()?, scale: scale.parse()? }
 		}
-		s if s.starts_with("Decimal256") => {
-			let scale: u8 = s[11..(s.len() - 1)].parse()?;
-			let precision = 77 - (f64::log10(scale as f64)).ceil() as u8;
-			DataType::Decimal { precision, scale }
+		("Decimal32", _) => decimal(10)?,
+		("Decimal64", _) => decimal(19)?,
+		("Decimal128", _) => decimal(39)?,
+		("Decimal256", _) => decimal(77)?,
+		("DateTime", None) => DataType::DateTime { precision: 0, timezone: None },
+		("DateTime", _) => {
+			DataType::DateTime { precision: 0, timezone: Some(single()?.replace('\'', "")) }
 		}
-		s if s.starts_with("DateTime(") || s == "DateTime" => DataType::DateTime {
-			precision: 0,
-			timezone: if s.len() > 9 { Some(s[9..(s.len() - 1)].replace('\'', "")) } else { None },
+		("DateTime64", Some(parts)) => match parts.as_slice() {
+			[precision] if !precision.is_empty() => {
+				DataType::DateTime { precision: precision.parse()?, timezone: None }
+			}
+			[precision, zone] if !precision.is_empty() => DataType::DateTime {
+				precision: precision.parse()?,
+				timezone: Some(zone.replace('\'', "")),
+			},
+			_ => return Err(malformed()),
 		},
-		s if s.starts_with("DateTime64") => {
-			let mut parts = s[11..(s.len() - 1)].splitn(2, ',');
-			let precision = parts.next().expect("Wrong DateTime64 type").parse::<u8>()?;
-			let timezone =
-				if let Some(zone) = parts.next() { Some(zone.replace('\'', "")) } else { None };
-			DataType::DateTime { precision, timezone }
-		}
-		s if s.starts_with("Nullable") => {
-			let sub_str = &s[9..(s.len() - 1)];
-			DataType::Nullable(Box::new(parse(sub_str)?))
-		}
-		s if s.starts_with("Array") => {
-			let sub_str = &s[6..(s.len() - 1)];
-			DataType::Array(Box::new(parse(sub_str)?))
+		("Nullable", _) => DataType::Nullable(Box::new(parse(single()?)?)),
+		("Array", _) => DataType::Array(Box::new(parse(single()?)?)),
+		("LowCardinality", _) => DataType::LowCardinality(Box::new(parse(single()?)?)),
+		("Map", _) => {
+			let (key, value) = pair()?;
+			DataType::Map { key: Box::new(parse(key)?), value: Box::new(parse(value)?) }
 		}
-		s if s.starts_with("LowCardinality") => {
-			let sub_str = &s[15..(s.len() - 1)];
-			DataType::LowCardinality(Box::new(parse(sub_str)?))
+		("Tuple", Some(elements)) if elements.iter().all(|element| !element.is_empty()) => {
+			DataType::Tuple(
+				elements.iter().map(|element| parse_sub_type(element)).collect::<IResult<_>>()?,
+			)
 		}
-		s if s.starts_with("Map") => {
-			let sub_str = &s[4..(s.len() - 1)];
-			let idx = sub_str.find(',').expect("Wrong map type!");
-			DataType::Map {
-				key: Box::new(parse(&sub_str[..idx])?),
-				value: Box::new(parse(&sub_str[(idx + 1)..])?),
-			}
+		("JSON" | "Json", None) => DataType::Json,
+		("Enum8" | "Enum16" | "AggregateFunction" | "SimpleAggregateFunction", Some(_)) => {
+			Err(IError::PromptError(format!("Unimplemented type: {}", type_str)))?
 		}
-		s if s.starts_with("Tuple") => {
-			let mut name_type_tuples = Vec::new();
-			let mut remaining = &s[6..(s.len() - 1)];
+		_ => Err(IError::PromptError(format!("Unsupported type: {}", type_str)))?,
+	})
+}
 
-			let mut skip = 0;
-			while !remaining.is_empty() {
-				if let Some(idx) = remaining.find_skip(',', skip) {
-					let name_type_str = &remaining[..idx];
-					if is_balanced_brackets(name_type_str) {
-						name_type_tuples.push(parse_sub_type(name_type_str)?);
-						remaining = &remaining[(idx + 1)..];
-						skip = 0;
-					} else {
-						skip += 1;
-					}
-				} else {
-					name_type_tuples.push(parse_sub_type(remaining)?);
-					remaining = "";
-				}
+/// Arguments of a type split at top level commas, e.g. `String, Array(Int8)`.
+fn split_args(args: &str) -> Vec<&str> {
+	let (mut parts, mut depth, mut start, mut quoted) = (Vec::new(), 0, 0, false);
+	for (idx, c) in args.char_indices() {
+		match c {
+			'\'' => quoted = !quoted,
+			'(' if !quoted => depth += 1,
+			')' if !quoted => depth -= 1,
+			',' if !quoted && depth == 0 => {
+				parts.push(args[start..idx].trim());
+				start = idx + 1;
 			}
-			DataType::Tuple(name_type_tuples)
+			_ => {}
 		}
-		"JSON" | "Json" => DataType::Json,
-		s if s.starts_with("Enum")
-			|| s.starts_with("AggregateFunction")
-			|| s.starts_with("SimpleAggregateFunction") =>
-		{
-			Err(IError::PromptError("Unimplemented type: ".to_owned()))?
-		}
-		_ => DataType::Unknown,
-	})
+	}
+	parts.push(args[start..].trim());
+	parts
 }
 
+/// An element of a tuple, named when it starts with an identifier followed by its type.
 fn parse_sub_type(name_type_str: &str) -> IResult<(String, DataType)> {
-	let name_type_vec = name_type_str.trim().splitn(2, ' ').collect::<Vec<_>>();
-	Ok(match name_type_vec.len() {
-		1 => ("".to_string(), parse(name_type_vec.get(0).unwrap())?),
-		2 => (name_type_vec.get(0).unwrap().to_string(), parse(name_type_vec.get(1).unwrap())?),
-		_ => unreachable!(),
-	})
+	match name_type_str.split_once(' ') {
+		Some((name, type_str)) if name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+			Ok((name.to_owned(), parse(type_str)?))
+		}
+		_ => Ok(("".to_owned(), parse(name_type_str)?)),
+	}
 }
 
 #[cfg(test)]
 mod tests {
 
 	use super::parse;
+	use crate::mapping::{column::DataType, database::DbType};
 
 	#[test]
 	fn test_parse() {
-		println!("{:?}", parse("Map(String, Int32)"));
-		println!("{:?}", parse("Array(Nullable(Int8))"));
-		println!("{:?}", parse("Map(LowCardinality(String), Int32)"));
-		println!("{:?}", parse("Map(String, Array(Decimal64(18)))"));
-		println!(
-			"{:?}",
-			parse("Tuple(Array(String), s Map(String, Int64) , s Map(String, Int64))")
-		);
+		let ch = |type_str: &str| parse(type_str).unwrap().to_type(DbType::ClickHouse);
+		for type_str in [
+			"Map(String,Int32)",
+			"Array(Nullable(Int8))",
+			"Map(LowCardinality(String),Int32)",
+			"FixedString(16)",
+			"Decimal(20, 4)",
+			"DateTime('UTC')",
+			"DateTime64(3, 'Asia/Shanghai')",
+		] {
+			assert_eq!(ch(type_str), parse(ch(type_str)).unwrap().to_type(DbType::ClickHouse));
+		}
+		assert!(matches!(
+			parse("Decimal(20, 4)").unwrap(),
+			DataType::Decimal { precision: 20, scale: 4 }
+		));
+		assert!(matches!(
+			parse("Tuple(Array(String), s Map(String, Int64) , Map(String, Int64))").unwrap(),
+			DataType::Tuple(elements) if elements.len() == 3
+				&& elements[1].0 == "s"
+				&& elements[2].0.is_empty()
+				&& matches!(elements[2].1, DataType::Map { .. })
+		));
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		for type_str in [
+			"FixedString",
+			"FixedString()",
+			"Decimal(10)",
+			"Decimal32",
+			"Map(String)",
+			"Nullable",
+			"Nullable(String",
+			"Array()",
+			"DateTime64",
+			"Tuple()",
+			"Strnig",
+			"Int32 x",
+			"Enum8('a' = 1)",
+		] {
+			assert!(parse(type_str).is_err(), "{}", type_str);
+		}
+	}
+
+	#[test]
+	fn test_to_ch_type() {
+		let datetime = |precision, timezone: Option<&str>| DataType::DateTime {
+			precision,
+			timezone: timezone.map(str::to_owned),
+		};
+		let cases = [
+			(DataType::Int { size: 8, unsigned: true }, "UInt64"),
+			(DataType::Int { size: 1, unsigned: false }, "Int8"),
+			(datetime(0, None), "DateTime"),
+			(datetime(0, Some("UTC")), "DateTime('UTC')"),
+			(datetime(3, None), "DateTime64(3)"),
+			(datetime(6, Some("Asia/Shanghai")), "DateTime64(6, 'Asia/Shanghai')"),
+			(
+				DataType::Tuple(vec![
+					("".to_owned(), DataType::String(None)),
+					("n".to_owned(), DataType::Int { size: 4, unsigned: false }),
+				]),
+				"Tuple(String,n Int32)",
+			),
+		];
+		for (data_type, expected) in cases {
+			assert_eq!(data_type.to_type(DbType::ClickHouse), expected);
+		}
 	}
 }
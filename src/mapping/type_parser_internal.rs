@@ -0,0 +1,265 @@
+//! Canonical, dialect-neutral type syntax used as the pivot format, e.g. `int64`, `uint8`,
+//! `decimal(20,4)`, `string(64)`, `text(65535)`, `bit(8)`, `datetime(3, 'UTC')`, `array<nullable<string>>`,
+//! `map<string, int32>` and `tuple<a: int32, string>`.
+
+use crate::error::{IError, IResult};
+
+use super::column::DataType;
+
+pub fn parse<T: AsRef<str>>(type_str: T) -> IResult<DataType> {
+	let mut parser = Parser { input: type_str.as_ref(), pos: 0 };
+	let data_type = parser.parse_type()?;
+	parser.skip_whitespace();
+	if parser.pos < parser.input.len() {
+		return Err(parser.error("unexpected trailing characters"));
+	}
+
+	Ok(data_type)
+}
+
+pub fn print(data_type: &DataType) -> String {
+	match data_type {
+		DataType::Int { size, unsigned } => {
+			format!("{}int{}", if *unsigned { "u" } else { "" }, size * 8)
+		}
+		DataType::Float(size) => format!("float{}", size * 8),
+		DataType::Decimal { precision, scale } => format!("decimal({},{})", precision, scale),
+		DataType::Bool => "bool".to_owned(),
+		DataType::Bit(size) => format!("bit({})", size),
+		DataType::Text(size) => format!("text({})", size),
+		DataType::Blob(size) => format!("blob({})", size),
+		DataType::String(Some(size)) => format!("string({})", size),
+		DataType::String(None) => "string".to_owned(),
+		DataType::Uuid => "uuid".to_owned(),
+		DataType::Date => "date".to_owned(),
+		DataType::Time => "time".to_owned(),
+		DataType::DateTime { precision, timezone: Some(tz) } => {
+			format!("datetime({}, '{}')", precision, tz)
+		}
+		DataType::DateTime { precision: 0, timezone: None } => "datetime".to_owned(),
+		DataType::DateTime { precision, timezone: None } => format!("datetime({})", precision),
+		DataType::Array(sub_type) => format!("array<{}>", print(sub_type)),
+		DataType::Map { key, value } => format!("map<{}, {}>", print(key), print(value)),
+		DataType::Nullable(sub_type) => format!("nullable<{}>", print(sub_type)),
+		DataType::LowCardinality(sub_type) => format!("low_cardinality<{}>", print(sub_type)),
+		DataType::Tuple(elements) => {
+			let joined = elements
+				.iter()
+				.map(|(name, sub_type)| {
+					if name.is_empty() {
+						print(sub_type)
+					} else {
+						format!("{}: {}", name, print(sub_type))
+					}
+				})
+				.collect::<Vec<_>>()
+				.join(", ");
+			format!("tuple<{}>", joined)
+		}
+		DataType::Json => "json".to_owned(),
+		DataType::Unknown => "unknown".to_owned(),
+	}
+}
+
+struct Parser<'a> {
+	input: &'a str,
+	pos: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn parse_type(&mut self) -> IResult<DataType> {
+		let ident = self.ident()?;
+		Ok(match ident {
+			"int8" | "int16" | "int24" | "int32" | "int64" | "int128" | "int256" => {
+				DataType::Int { size: ident[3..].parse::<usize>()? / 8, unsigned: false }
+			}
+			"uint8" | "uint16" | "uint24" | "uint32" | "uint64" | "uint128" | "uint256" => {
+				DataType::Int { size: ident[4..].parse::<usize>()? / 8, unsigned: true }
+			}
+			"float32" => DataType::Float(4),
+			"float64" => DataType::Float(8),
+			"decimal" => {
+				self.expect('(')?;
+				let precision = self.number()?.parse()?;
+				self.expect(',')?;
+				let scale = self.number()?.parse()?;
+				self.expect(')')?;
+				DataType::Decimal { precision, scale }
+			}
+			"bool" => DataType::Bool,
+			"bit" => DataType::Bit(self.size()?),
+			"text" => DataType::Text(self.size()?),
+			"blob" => DataType::Blob(self.size()?),
+			"string" => {
+				if self.eat('(') {
+					let size = self.number()?.parse()?;
+					self.expect(')')?;
+					DataType::String(Some(size))
+				} else {
+					DataType::String(None)
+				}
+			}
+			"uuid" => DataType::Uuid,
+			"date" => DataType::Date,
+			"time" => DataType::Time,
+			"datetime" => {
+				let mut precision = 0;
+				let mut timezone = None;
+				if self.eat('(') {
+					precision = self.number()?.parse()?;
+					if self.eat(',') {
+						timezone = Some(self.quoted()?.to_owned());
+					}
+					self.expect(')')?;
+				}
+				DataType::DateTime { precision, timezone }
+			}
+			"array" => DataType::Array(Box::new(self.single_arg()?)),
+			"nullable" => DataType::Nullable(Box::new(self.single_arg()?)),
+			"low_cardinality" => DataType::LowCardinality(Box::new(self.single_arg()?)),
+			"map" => {
+				self.expect('<')?;
+				let key = self.parse_type()?;
+				self.expect(',')?;
+				let value = self.parse_type()?;
+				self.expect('>')?;
+				DataType::Map { key: Box::new(key), value: Box::new(value) }
+			}
+			"tuple" => {
+				self.expect('<')?;
+				let mut elements = Vec::new();
+				loop {
+					elements.push(self.tuple_element()?);
+					if !self.eat(',') {
+						break;
+					}
+				}
+				self.expect('>')?;
+				DataType::Tuple(elements)
+			}
+			"json" => DataType::Json,
+			"unknown" => DataType::Unknown,
+			_ => Err(self.error(&format!("unknown type `{}`", ident)))?,
+		})
+	}
+
+	/// `(n)` following types which require a size.
+	fn size(&mut self) -> IResult<usize> {
+		self.expect('(')?;
+		let size = self.number()?.parse()?;
+		self.expect(')')?;
+		Ok(size)
+	}
+
+	fn single_arg(&mut self) -> IResult<DataType> {
+		self.expect('<')?;
+		let sub_type = self.parse_type()?;
+		self.expect('>')?;
+		Ok(sub_type)
+	}
+
+	/// Either `name: type` or an unnamed `type`.
+	fn tuple_element(&mut self) -> IResult<(String, DataType)> {
+		let start = self.pos;
+		let name = self.ident()?;
+		if self.eat(':') {
+			Ok((name.to_owned(), self.parse_type()?))
+		} else {
+			self.pos = start;
+			Ok(("".to_owned(), self.parse_type()?))
+		}
+	}
+
+	fn ident(&mut self) -> IResult<&'a str> {
+		self.skip_whitespace();
+		let len = self.rest().find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'));
+		let ident = &self.rest()[..len.unwrap_or(self.rest().len())];
+		if ident.is_empty() {
+			return Err(self.error("expect identifier"));
+		}
+
+		self.pos += ident.len();
+		Ok(ident)
+	}
+
+	fn number(&mut self) -> IResult<&'a str> {
+		self.skip_whitespace();
+		let len = self.rest().find(|c: char| !c.is_ascii_digit());
+		let number = &self.rest()[..len.unwrap_or(self.rest().len())];
+		if number.is_empty() {
+			return Err(self.error("expect number"));
+		}
+
+		self.pos += number.len();
+		Ok(number)
+	}
+
+	fn quoted(&mut self) -> IResult<&'a str> {
+		self.expect('\'')?;
+		let len = self.rest().find('\'').ok_or(self.error("unclosed quote"))?;
+		let quoted = &self.rest()[..len];
+		self.pos += len + 1;
+		Ok(quoted)
+	}
+
+	fn expect(&mut self, c: char) -> IResult<()> {
+		if self.eat(c) {
+			Ok(())
+		} else {
+			Err(self.error(&format!("expect `{}`", c)))
+		}
+	}
+
+	fn eat(&mut self, c: char) -> bool {
+		self.skip_whitespace();
+		if self.rest().starts_with(c) {
+			self.pos += c.len_utf8();
+			true
+		} else {
+			false
+		}
+	}
+
+	fn skip_whitespace(&mut self) {
+		self.pos += self.rest().len() - self.rest().trim_start().len();
+	}
+
+	fn rest(&self) -> &'a str {
+		&self.input[self.pos..]
+	}
+
+	fn error(&self, msg: &str) -> IError {
+		IError::PromptError(format!("Invalid type `{}` at {}: {}", self.input, self.pos, msg))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse, print};
+
+	#[test]
+	fn test_round_trip() {
+		for type_str in [
+			"int64",
+			"uint8",
+			"int24",
+			"decimal(20,4)",
+			"string(64)",
+			"text(16777215)",
+			"blob(255)",
+			"bit(12)",
+			"datetime",
+			"datetime(3, 'Asia/Shanghai')",
+			"array<nullable<string>>",
+			"map<low_cardinality<string>, array<decimal(18,2)>>",
+			"tuple<a: int32, string, b: map<string, int64>>",
+		] {
+			assert_eq!(print(&parse(type_str).unwrap()), type_str);
+		}
+
+		assert_eq!(print(&parse(" array < nullable<int8> > ").unwrap()), "array<nullable<int8>>");
+		assert!(parse("array<int8").is_err());
+		assert!(parse("int7").is_err());
+		assert!(parse("string extra").is_err());
+	}
+}
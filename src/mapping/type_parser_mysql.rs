@@ -2,7 +2,65 @@ use mysql::consts::ColumnType;
 
 use crate::error::{IError, IResult};
 
-use super::column::DataType;
+use super::column::{DataType, LOB_SIZES};
+
+/// Parse a column type as written in `CREATE TABLE`, e.g. `bigint(20) unsigned`.
+pub fn parse_str<T: AsRef<str>>(type_str: T) -> IResult<DataType> {
+	let lower = type_str.as_ref().trim().to_lowercase();
+	let unsigned = lower.contains("unsigned");
+	let (name, args) = match lower.find('(') {
+		Some(idx) => {
+			let end = lower.rfind(')').unwrap_or(lower.len());
+			(lower[..idx].trim(), lower[(idx + 1)..end].split(',').map(str::trim).collect())
+		}
+		None => (lower.split_whitespace().next().unwrap_or(""), Vec::new()),
+	};
+	let arg = |idx: usize| args.get(idx).filter(|arg| !arg.is_empty());
+
+	Ok(match name {
+		"tinyint" if arg(0) == Some(&"1") && !unsigned => DataType::Bool,
+		"tinyint" => DataType::Int { size: 1, unsigned },
+		"smallint" => DataType::Int { size: 2, unsigned },
+		"mediumint" => DataType::Int { size: 3, unsigned },
+		"int" | "integer" => DataType::Int { size: 4, unsigned },
+		"bigint" => DataType::Int { size: 8, unsigned },
+		"float" => DataType::Float(4),
+		"double" | "real" => DataType::Float(8),
+		"decimal" | "numeric" | "dec" => DataType::Decimal {
+			precision: arg(0).map(|p| p.parse()).transpose()?.unwrap_or(10),
+			scale: arg(1).map(|s| s.parse()).transpose()?.unwrap_or(0),
+		},
+		"bool" | "boolean" => DataType::Bool,
+		"bit" => match arg(0).map(|size| size.parse()).transpose()? {
+			None | Some(1) => DataType::Bool,
+			Some(size) => DataType::Bit(size),
+		},
+		"char" | "varchar" | "binary" | "varbinary" => {
+			DataType::String(arg(0).map(|size| size.parse()).transpose()?)
+		}
+		"tinytext" => DataType::Text(LOB_SIZES[0]),
+		"text" => {
+			DataType::Text(arg(0).map(|size| size.parse()).transpose()?.unwrap_or(LOB_SIZES[1]))
+		}
+		"mediumtext" => DataType::Text(LOB_SIZES[2]),
+		"longtext" => DataType::Text(LOB_SIZES[3]),
+		"tinyblob" => DataType::Blob(LOB_SIZES[0]),
+		"blob" => {
+			DataType::Blob(arg(0).map(|size| size.parse()).transpose()?.unwrap_or(LOB_SIZES[1]))
+		}
+		"mediumblob" => DataType::Blob(LOB_SIZES[2]),
+		"longblob" => DataType::Blob(LOB_SIZES[3]),
+		"date" => DataType::Date,
+		"time" => DataType::Time,
+		"datetime" | "timestamp" => DataType::DateTime {
+			precision: arg(0).map(|p| p.parse()).transpose()?.unwrap_or(0),
+			timezone: None,
+		},
+		"year" => DataType::Int { size: 2, unsigned: true },
+		"json" => DataType::Json,
+		_ => Err(IError::PromptError(format!("Unsupported type: {}", type_str.as_ref())))?,
+	})
+}
 
 pub fn parse(column_type: ColumnType) -> IResult<DataType> {
 	Ok(match column_type {
@@ -12,8 +70,8 @@ pub fn parse(column_type: ColumnType) -> IResult<DataType> {
 		ColumnType::MYSQL_TYPE_TINY => DataType::Int { size: 1, unsigned: false },
 		ColumnType::MYSQL_TYPE_SHORT => DataType::Int { size: 2, unsigned: false },
 		ColumnType::MYSQL_TYPE_INT24 => DataType::Int { size: 3, unsigned: false },
-		ColumnType::MYSQL_TYPE_LONG => DataType::Int { size: 8, unsigned: false },
-		ColumnType::MYSQL_TYPE_LONGLONG => DataType::Int { size: 16, unsigned: false },
+		ColumnType::MYSQL_TYPE_LONG => DataType::Int { size: 4, unsigned: false },
+		ColumnType::MYSQL_TYPE_LONGLONG => DataType::Int { size: 8, unsigned: false },
 		ColumnType::MYSQL_TYPE_FLOAT => DataType::Float(4),
 		ColumnType::MYSQL_TYPE_DOUBLE => DataType::Float(8),
 		ColumnType::MYSQL_TYPE_TIMESTAMP | ColumnType::MYSQL_TYPE_TIMESTAMP2 => {
@@ -24,7 +82,7 @@ pub fn parse(column_type: ColumnType) -> IResult<DataType> {
 		ColumnType::MYSQL_TYPE_DATETIME | ColumnType::MYSQL_TYPE_DATETIME2 => {
 			DataType::DateTime { precision: 3, timezone: None }
 		}
-		ColumnType::MYSQL_TYPE_YEAR => todo!(),
+		ColumnType::MYSQL_TYPE_YEAR => DataType::Int { size: 2, unsigned: true },
 		ColumnType::MYSQL_TYPE_VARCHAR
 		| ColumnType::MYSQL_TYPE_STRING
 		| ColumnType::MYSQL_TYPE_VAR_STRING => DataType::String(None),
@@ -44,3 +102,75 @@ pub fn parse(column_type: ColumnType) -> IResult<DataType> {
 		}
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	use mysql::consts::ColumnType;
+
+	use super::{parse, parse_str};
+	use crate::mapping::{column::DataType, database::DbType};
+
+	#[test]
+	fn test_parse_str() {
+		assert!(matches!(
+			parse_str("bigint(20) unsigned").unwrap(),
+			DataType::Int { size: 8, unsigned: true }
+		));
+		assert!(matches!(parse_str("tinyint(1)").unwrap(), DataType::Bool));
+		assert!(matches!(
+			parse_str("DECIMAL(20, 4)").unwrap(),
+			DataType::Decimal { precision: 20, scale: 4 }
+		));
+		assert!(matches!(parse_str("varchar(64)").unwrap(), DataType::String(Some(64))));
+		assert!(matches!(
+			parse_str("datetime(3)").unwrap(),
+			DataType::DateTime { precision: 3, timezone: None }
+		));
+		assert!(parse_str("enum('a','b')").is_err());
+	}
+
+	#[test]
+	fn test_large_objects_and_bits() {
+		let mysql = |type_str: &str| parse_str(type_str).unwrap().to_type(DbType::MySQL);
+		for type_str in [
+			"TINYTEXT",
+			"TEXT",
+			"MEDIUMTEXT",
+			"LONGTEXT",
+			"TINYBLOB",
+			"BLOB",
+			"MEDIUMBLOB",
+			"LONGBLOB",
+			"BIT(12)",
+		] {
+			assert_eq!(mysql(type_str), type_str);
+		}
+		assert_eq!(mysql("text(70000)"), "MEDIUMTEXT");
+		assert!(matches!(parse_str("bit").unwrap(), DataType::Bool));
+		assert!(matches!(parse_str("bit(1)").unwrap(), DataType::Bool));
+		assert!(matches!(parse_str("bit(64)").unwrap(), DataType::Bit(64)));
+
+		let clickhouse = |type_str: &str| parse_str(type_str).unwrap().to_type(DbType::ClickHouse);
+		assert_eq!(clickhouse("longtext"), "String");
+		assert_eq!(clickhouse("blob"), "String");
+		assert_eq!(clickhouse("bit(12)"), "UInt16");
+		assert_eq!(clickhouse("bit(3)"), "UInt8");
+	}
+
+	#[test]
+	fn test_parse_column_type() {
+		let mysql = |column_type| parse(column_type).unwrap().to_type(DbType::MySQL);
+		assert_eq!(
+			mysql(ColumnType::MYSQL_TYPE_LONG),
+			parse_str("int").unwrap().to_type(DbType::MySQL)
+		);
+		assert_eq!(
+			mysql(ColumnType::MYSQL_TYPE_LONGLONG),
+			parse_str("bigint").unwrap().to_type(DbType::MySQL)
+		);
+		assert_eq!(
+			mysql(ColumnType::MYSQL_TYPE_YEAR),
+			parse_str("year").unwrap().to_type(DbType::MySQL)
+		);
+	}
+}
@@ -0,0 +1,320 @@
+use std::{fs, path::Path};
+
+use regex::Regex;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+	conn::DBClient,
+	error::{IError, IResult},
+	mapping::{column::DataType, database::DbType, diff, table::Table},
+	store::{conn_conf::ConnConf, Store},
+};
+
+/// Declarative description of a schema sync, loaded from a TOML or YAML file.
+#[derive(Debug, Deserialize)]
+pub struct SyncSpec {
+	pub source: ConnSpec,
+	pub target: ConnSpec,
+	pub databases: Vec<String>,
+	/// Regex patterns matched against `database.table`, empty includes everything.
+	#[serde(default)]
+	pub include: Vec<String>,
+	#[serde(default)]
+	pub exclude: Vec<String>,
+	#[serde(default)]
+	pub overrides: Vec<TypeOverride>,
+	#[serde(default)]
+	pub naming: NamingRule,
+	#[serde(default)]
+	pub engine: EngineTemplate,
+	/// Also drop columns which only exist in target.
+	#[serde(default)]
+	pub drop_removed: bool,
+}
+
+/// Either a stored connection referenced by `conn`, or an inline one whose fields may refer to
+/// environment variables as `${NAME}`.
+#[derive(Debug, Default, Deserialize)]
+pub struct ConnSpec {
+	pub conn: Option<String>,
+	pub r#type: Option<DbType>,
+	#[serde(default)]
+	pub url: String,
+	#[serde(default)]
+	pub username: String,
+	#[serde(default)]
+	pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TypeOverride {
+	/// Regex pattern matched against `database.table.column`.
+	pub column: String,
+	/// Column type written in the dialect of target.
+	pub r#type: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NamingRule {
+	pub database: Option<String>,
+	pub table_prefix: String,
+	pub table_suffix: String,
+	pub lowercase: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct EngineTemplate {
+	/// Engine clause, `{database}` and `{table}` are replaced by the target names.
+	pub template: String,
+	pub order_by: Vec<String>,
+	pub primary_keys: Vec<String>,
+}
+
+/// Planned statements of a single table.
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct TablePlan {
+	pub source: String,
+	pub target: String,
+	pub statements: Vec<String>,
+}
+
+impl SyncSpec {
+	pub fn load(path: &Path) -> IResult<SyncSpec> {
+		let content = fs::read_to_string(path)?;
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some("yaml") | Some("yml") => Ok(serde_yaml::from_str(&content)?),
+			_ => Ok(toml::from_str(&content)?),
+		}
+	}
+
+	/// Build source and target tables and produce the statements of every matched table.
+	pub fn plan(&self, store: &dyn Store) -> IResult<Vec<TablePlan>> {
+		let (_, source_client) = self.source.connect(store, "spec-source")?;
+		let (_, target_client) = self.target.connect(store, "spec-target")?;
+		let include = compile(&self.include)?;
+		let exclude = compile(&self.exclude)?;
+		let overrides = self
+			.overrides
+			.iter()
+			.map(|item| Ok((Regex::new(&item.column)?, &item.r#type)))
+			.collect::<IResult<Vec<_>>>()?;
+
+		let mut plans = Vec::new();
+		for database in &self.databases {
+			for table in source_client.tables(database)? {
+				let qualified = format!("{}.{}", database, table);
+				if (!include.is_empty() && !include.iter().any(|re| re.is_match(&qualified)))
+					|| exclude.iter().any(|re| re.is_match(&qualified))
+				{
+					continue;
+				}
+
+				let mut source = source_client.table(database, &table)?;
+				let target = target_client
+					.table(&self.naming.database(database), &self.naming.table(&table))?;
+				let dialect = target.r#type;
+				for column in &mut source.columns {
+					let qualified = format!("{}.{}", qualified, column.name);
+					if let Some((_, type_str)) =
+						overrides.iter().find(|(re, _)| re.is_match(&qualified))
+					{
+						column.r#type = DataType::parse(type_str.to_string(), dialect)?;
+					}
+				}
+
+				let source = self.engine.apply(source, &target);
+				let (_, statements) = diff::plan(&source, &target, self.drop_removed)?;
+				plans.push(TablePlan {
+					source: qualified,
+					target: format!("{}.{}", target.database, target.name),
+					statements,
+				});
+			}
+		}
+
+		Ok(plans)
+	}
+
+	/// Execute planned statements on target.
+	pub fn apply(&self, store: &dyn Store, plans: &[TablePlan]) -> IResult<()> {
+		let (conf, client) = self.target.connect(store, "spec-target")?;
+		for plan in plans {
+			let (database, table) = plan.target.split_once('.').unwrap_or(("", &plan.target));
+			for sql in &plan.statements {
+				client.execute_ddl(store, &conf.uuid, database, table, sql)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl ConnSpec {
+	/// The stored connection, or the inline one under a uuid derived from its type, url and user,
+	/// so the same endpoint shares its client and DDL history across specs and runs.
+	fn conf(&self, store: &dyn Store, name: &str) -> IResult<ConnConf> {
+		let conf = match &self.conn {
+			Some(name_or_uuid) => store.find_conn(name_or_uuid)?,
+			None => {
+				let url = expand_env(&self.url)?;
+				let username = expand_env(&self.username)?;
+				let key = format!("{:?}|{}|{}", self.r#type, url, username);
+				ConnConf {
+					uuid: Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes()).to_string(),
+					name: name.to_owned(),
+					db_type: self.r#type,
+					url,
+					username,
+					password: expand_env(&self.password)?,
+					production: false,
+				}
+			}
+		};
+		Ok(conf)
+	}
+
+	fn connect(&self, store: &dyn Store, name: &str) -> IResult<(ConnConf, DBClient)> {
+		let conf = self.conf(store, name)?;
+		let client = DBClient::get_or_init(conf.clone().try_into()?)?;
+		Ok((conf, client))
+	}
+}
+
+impl NamingRule {
+	fn database(&self, database: &str) -> String {
+		self.database.clone().unwrap_or_else(|| database.to_owned())
+	}
+
+	fn table(&self, table: &str) -> String {
+		let name = format!("{}{}{}", self.table_prefix, table, self.table_suffix);
+		if self.lowercase {
+			name.to_lowercase()
+		} else {
+			name
+		}
+	}
+}
+
+impl EngineTemplate {
+	fn apply(&self, source: Table, target: &Table) -> Table {
+		let mut table = Table {
+			name: target.name.clone(),
+			database: target.database.clone(),
+			r#type: target.r#type,
+			engine: self
+				.template
+				.replace("{database}", &target.database)
+				.replace("{table}", &target.name),
+			..source
+		};
+		if !self.order_by.is_empty() {
+			table.order_by = self.order_by.clone();
+		}
+		if !self.primary_keys.is_empty() {
+			table.primary_keys = self.primary_keys.clone();
+		}
+
+		table
+	}
+}
+
+fn compile(patterns: &[String]) -> IResult<Vec<Regex>> {
+	Ok(patterns.iter().map(|pattern| Regex::new(pattern)).collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Replace `${NAME}` with the value of environment variable `NAME`.
+fn expand_env(value: &str) -> IResult<String> {
+	let re = Regex::new(r"\$\{(\w+)\}")?;
+	let mut expanded = value.to_owned();
+	for cap in re.captures_iter(value) {
+		let env = std::env::var(&cap[1]).map_err(|_| {
+			IError::PromptError(format!("Environment variable not set: {}", &cap[1]))
+		})?;
+		expanded = expanded.replace(&cap[0], &env);
+	}
+
+	Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{expand_env, ConnSpec, SyncSpec};
+	use crate::{
+		mapping::database::DbType,
+		store::{conn_conf::ConnConf, memory::MemoryStore},
+	};
+
+	#[test]
+	fn test_parse_spec() {
+		let spec: SyncSpec = toml::from_str(
+			r#"
+			databases = ["app"]
+			include = ["^app\\.user_"]
+
+			[source]
+			conn = "mysql-prod"
+
+			[target]
+			type = "ClickHouse"
+			url = "${CH_URL}"
+
+			[[overrides]]
+			column = "\\.id$"
+			type = "UInt64"
+
+			[naming]
+			table_prefix = "ods_"
+			lowercase = true
+
+			[engine]
+			template = "ReplicatedMergeTree('/tables/{database}/{table}', '{replica}')"
+			"#,
+		)
+		.unwrap();
+		assert_eq!(spec.source.conn.as_deref(), Some("mysql-prod"));
+		assert_eq!(spec.target.r#type, Some(DbType::ClickHouse));
+		assert_eq!(spec.naming.table("User_Info"), "ods_user_info");
+		assert_eq!(spec.overrides[0].r#type, "UInt64");
+
+		let yaml: SyncSpec = serde_yaml::from_str(
+			"databases: [app]\nsource:\n  conn: mysql-prod\ntarget:\n  conn: ch-prod\n",
+		)
+		.unwrap();
+		assert_eq!(yaml.target.conn.as_deref(), Some("ch-prod"));
+	}
+
+	#[test]
+	fn test_expand_env() {
+		std::env::set_var("SPEC_TEST_HOST", "127.0.0.1");
+		assert_eq!(expand_env("http://${SPEC_TEST_HOST}:8123").unwrap(), "http://127.0.0.1:8123");
+		assert!(expand_env("${SPEC_TEST_NOT_EXISTS}").is_err());
+	}
+
+	#[test]
+	fn test_inline_conn_uuid() {
+		let store = &MemoryStore::default();
+		let spec = |url: &str| ConnSpec {
+			r#type: Some(DbType::ClickHouse),
+			url: url.to_owned(),
+			username: "default".to_owned(),
+			..Default::default()
+		};
+		let conf = spec("http://ch-a:8123").conf(store, "spec-source").unwrap();
+		assert_eq!(conf.name, "spec-source");
+		assert_eq!(conf.uuid, spec("http://ch-a:8123").conf(store, "spec-target").unwrap().uuid);
+		assert_ne!(conf.uuid, spec("http://ch-b:8123").conf(store, "spec-source").unwrap().uuid);
+		assert!(uuid::Uuid::parse_str(&conf.uuid).is_ok());
+
+		let dev = ConnConf { uuid: "1".to_owned(), name: "dev".to_owned(), ..Default::default() };
+		store.conns.lock().unwrap().push(dev);
+		let stored = ConnSpec { conn: Some("dev".to_owned()), ..Default::default() };
+		assert_eq!(stored.conf(store, "spec-source").unwrap().uuid, "1");
+		assert!(ConnSpec { conn: Some("prod".to_owned()), ..Default::default() }
+			.conf(store, "spec-source")
+			.is_err());
+	}
+}
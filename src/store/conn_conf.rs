@@ -1,11 +1,12 @@
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::{error::IResult, mapping::database::DbType};
+use crate::{conn::endpoint, error::IResult, mapping::database::DbType};
 
 use super::get_conn;
 
 #[derive(Debug, Default, Validate, Clone)]
+#[validate(schema(function = "validate_username", skip_on_field_errors = false))]
 pub struct ConnConf {
 	pub uuid: String,
 	#[validate(length(min = 1))]
@@ -14,9 +15,39 @@ pub struct ConnConf {
 	pub db_type: Option<DbType>,
 	#[validate(length(min = 1))]
 	pub url: String,
-	#[validate(length(min = 1))]
+	/// Required unless the connection is a fixture or a schema directory.
 	pub username: String,
 	pub password: String,
+	/// Destructive actions on production connections need the name typed to be confirmed.
+	pub production: bool,
+}
+
+/// A stored connection without its credentials, including those written in its url.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConnSummary {
+	pub uuid: String,
+	pub name: String,
+	pub r#type: DbType,
+	pub url: String,
+}
+
+impl From<ConnConf> for ConnSummary {
+	fn from(conf: ConnConf) -> Self {
+		ConnSummary {
+			uuid: conf.uuid,
+			name: conf.name,
+			r#type: conf.db_type.unwrap_or_default(),
+			url: endpoint::strip_credentials(&conf.url),
+		}
+	}
+}
+
+fn validate_username(conf: &ConnConf) -> Result<(), ValidationError> {
+	let local = matches!(conf.db_type, Some(DbType::Fixture | DbType::Directory));
+	if conf.username.is_empty() && !local {
+		return Err(ValidationError::new("username"));
+	}
+	Ok(())
 }
 
 pub fn insert_or_update(conf: &ConnConf) -> IResult<()> {
@@ -30,10 +61,10 @@ pub fn insert_or_update(conf: &ConnConf) -> IResult<()> {
 pub fn insert(conf: &ConnConf) -> IResult<()> {
 	conf.validate()?;
 
-	let conn = get_conn();
+	let conn = get_conn()?;
 	let uuid = Uuid::new_v4().to_string();
 	conn.execute(
-		"INSERT INTO t_conn_conf(uuid, name, type, url, username, password) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+		"INSERT INTO t_conn_conf(uuid, name, type, url, username, password, production) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
 		(
 			&uuid,
 			&conf.name,
@@ -41,6 +72,7 @@ pub fn insert(conf: &ConnConf) -> IResult<()> {
 			&conf.url,
 			&conf.username,
 			&conf.password,
+			&conf.production,
 		),
 	)?;
 
@@ -50,15 +82,16 @@ pub fn insert(conf: &ConnConf) -> IResult<()> {
 pub fn update(conf: &ConnConf) -> IResult<()> {
 	conf.validate()?;
 
-	let conn = get_conn();
+	let conn = get_conn()?;
 	conn.execute(
-		"UPDATE t_conn_conf set name=?1, type=?2, url=?3, username=?4, password=?5 WHERE uuid = ?6",
+		"UPDATE t_conn_conf set name=?1, type=?2, url=?3, username=?4, password=?5, production=?6 WHERE uuid = ?7",
 		(
 			&conf.name,
 			&conf.db_type.as_ref().map(|db_ty| db_ty.to_string()).unwrap(),
 			&conf.url,
 			&conf.username,
 			&conf.password,
+			&conf.production,
 			&conf.uuid,
 		),
 	)?;
@@ -67,15 +100,15 @@ pub fn update(conf: &ConnConf) -> IResult<()> {
 }
 
 pub fn delete(uuid: &String) -> IResult<()> {
-	let conn = get_conn();
+	let conn = get_conn()?;
 	conn.execute("DELETE FROM t_conn_conf WHERE uuid = ?1", (uuid,))?;
 	Ok(())
 }
 
 pub fn query_by_uuid(uuid: &String) -> IResult<ConnConf> {
-	let conn = get_conn();
+	let conn = get_conn()?;
 	Ok(conn.query_row(
-		"SELECT uuid, name, type, url, username, password FROM t_conn_conf where uuid = ?1 ",
+		"SELECT uuid, name, type, url, username, password, production FROM t_conn_conf where uuid = ?1 ",
 		[uuid],
 		|row| {
 			Ok(ConnConf {
@@ -85,15 +118,16 @@ pub fn query_by_uuid(uuid: &String) -> IResult<ConnConf> {
 				url: row.get(3)?,
 				username: row.get(4)?,
 				password: row.get(5)?,
+				production: row.get(6)?,
 			})
 		},
 	)?)
 }
 
 pub fn list_all() -> IResult<Vec<ConnConf>> {
-	let conn = get_conn();
-	let mut stmt =
-		conn.prepare("SELECT uuid, name, type, url, username, password FROM t_conn_conf")?;
+	let conn = get_conn()?;
+	let mut stmt = conn
+		.prepare("SELECT uuid, name, type, url, username, password, production FROM t_conn_conf")?;
 	let list = stmt
 		.query_map((), |row| {
 			Ok(ConnConf {
@@ -103,6 +137,7 @@ pub fn list_all() -> IResult<Vec<ConnConf>> {
 				url: row.get(3)?,
 				username: row.get(4)?,
 				password: row.get(5)?,
+				production: row.get(6)?,
 			})
 		})?
 		.map(|item| item.unwrap())
@@ -0,0 +1,144 @@
+use std::time::Instant;
+
+use chrono::Local;
+use rusqlite::Row;
+use tracing::warn;
+
+use crate::error::IResult;
+
+use super::{get_conn, Store};
+
+const SELECT_COLUMNS: &'static str = "SELECT id, executed_at, executor, conn_uuid, database, \
+	table_name, statement, duration_ms, success, message FROM t_ddl_history";
+
+#[derive(Debug, Default, Clone)]
+pub struct DdlHistory {
+	pub id: i64,
+	pub executed_at: String,
+	pub executor: String,
+	pub conn_uuid: String,
+	pub database: String,
+	pub table: String,
+	pub statement: String,
+	pub duration_ms: i64,
+	pub success: bool,
+	pub message: String,
+}
+
+/// Conditions used to narrow down the history list, empty fields match everything.
+#[derive(Debug, Default, Clone)]
+pub struct HistoryFilter {
+	pub conn_uuid: Option<String>,
+	pub keyword: String,
+	pub failed_only: bool,
+}
+
+impl DdlHistory {
+	pub fn new(conn_uuid: &str, database: &str, table: &str, statement: &str) -> DdlHistory {
+		DdlHistory {
+			executed_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+			executor: current_user(),
+			conn_uuid: conn_uuid.to_owned(),
+			database: database.to_owned(),
+			table: table.to_owned(),
+			statement: statement.to_owned(),
+			..Default::default()
+		}
+	}
+
+	fn from_row(row: &Row) -> rusqlite::Result<DdlHistory> {
+		Ok(DdlHistory {
+			id: row.get(0)?,
+			executed_at: row.get(1)?,
+			executor: row.get(2)?,
+			conn_uuid: row.get(3)?,
+			database: row.get(4)?,
+			table: row.get(5)?,
+			statement: row.get(6)?,
+			duration_ms: row.get(7)?,
+			success: row.get(8)?,
+			message: row.get(9)?,
+		})
+	}
+}
+
+/// Run `execute` and record it as an execution of `statement` in `store`, failing to record it
+/// only logs a warning.
+pub fn record<F>(
+	store: &dyn Store,
+	conn_uuid: &str,
+	database: &str,
+	table: &str,
+	statement: &str,
+	execute: F,
+) -> IResult<()>
+where
+	F: FnOnce() -> IResult<()>,
+{
+	let mut history = DdlHistory::new(conn_uuid, database, table, statement);
+	let start = Instant::now();
+	let result = execute();
+
+	history.duration_ms = start.elapsed().as_millis() as i64;
+	history.success = result.is_ok();
+	if let Err(e) = &result {
+		history.message = e.to_string();
+	}
+	if let Err(e) = store.save_history(&history) {
+		warn!("Failed to record ddl history, statement: {}, error: {}", statement, e);
+	}
+
+	result
+}
+
+pub fn insert(history: &DdlHistory) -> IResult<i64> {
+	let conn = get_conn()?;
+	conn.execute(
+		"INSERT INTO t_ddl_history(executed_at, executor, conn_uuid, database, table_name, \
+		statement, duration_ms, success, message) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+		(
+			&history.executed_at,
+			&history.executor,
+			&history.conn_uuid,
+			&history.database,
+			&history.table,
+			&history.statement,
+			&history.duration_ms,
+			&history.success,
+			&history.message,
+		),
+	)?;
+
+	Ok(conn.last_insert_rowid())
+}
+
+pub fn query_by_id(id: i64) -> IResult<DdlHistory> {
+	let conn = get_conn()?;
+	Ok(conn.query_row(&format!("{} WHERE id = ?1", SELECT_COLUMNS), [id], DdlHistory::from_row)?)
+}
+
+pub fn query(filter: &HistoryFilter) -> IResult<Vec<DdlHistory>> {
+	let conn = get_conn()?;
+	let mut stmt = conn.prepare(&format!(
+		"{} WHERE (?1 IS NULL OR conn_uuid = ?1) AND (?2 = '' OR statement LIKE ?3 \
+		OR database LIKE ?3 OR table_name LIKE ?3) AND (?4 = 0 OR success = 0) ORDER BY id DESC",
+		SELECT_COLUMNS
+	))?;
+	let list = stmt
+		.query_map(
+			(
+				&filter.conn_uuid,
+				&filter.keyword,
+				format!("%{}%", filter.keyword),
+				filter.failed_only,
+			),
+			DdlHistory::from_row,
+		)?
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(list)
+}
+
+fn current_user() -> String {
+	std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default()
+}
@@ -0,0 +1,133 @@
+use std::{
+	collections::HashMap,
+	io,
+	path::{Path, PathBuf},
+	sync::Mutex,
+};
+
+use crate::{
+	conf::Settings,
+	error::{IError, IResult},
+};
+
+use super::{
+	conn_conf::ConnConf,
+	ddl_history::{DdlHistory, HistoryFilter},
+	snapshot::Snapshot,
+	Store,
+};
+
+/// Keeps everything in memory, for tests of the GUI and the http api.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+	pub conns: Mutex<Vec<ConnConf>>,
+	pub histories: Mutex<Vec<DdlHistory>>,
+	pub snapshots: Mutex<Vec<Snapshot>>,
+	pub settings: Mutex<Settings>,
+	pub files: Mutex<HashMap<PathBuf, String>>,
+}
+
+impl Store for MemoryStore {
+	fn list_conns(&self) -> IResult<Vec<ConnConf>> {
+		Ok(self.conns.lock().unwrap().clone())
+	}
+
+	fn save_conn(&self, conf: &ConnConf) -> IResult<()> {
+		let mut conns = self.conns.lock().unwrap();
+		match conns.iter_mut().find(|saved| !conf.uuid.is_empty() && saved.uuid == conf.uuid) {
+			Some(saved) => *saved = conf.clone(),
+			None => {
+				let uuid = format!("conn-{}", conns.len());
+				conns.push(ConnConf { uuid, ..conf.clone() });
+			}
+		}
+		Ok(())
+	}
+
+	fn delete_conn(&self, uuid: &String) -> IResult<()> {
+		self.conns.lock().unwrap().retain(|conf| &conf.uuid != uuid);
+		Ok(())
+	}
+
+	fn query_conn(&self, uuid: &String) -> IResult<ConnConf> {
+		self.list_conns()?
+			.into_iter()
+			.find(|conf| &conf.uuid == uuid)
+			.ok_or(IError::NotFoundError(format!("connection {}", uuid)))
+	}
+
+	fn query_histories(&self, filter: &HistoryFilter) -> IResult<Vec<DdlHistory>> {
+		let histories = self.histories.lock().unwrap();
+		Ok(histories
+			.iter()
+			.rev()
+			.filter(|history| {
+				filter.conn_uuid.as_ref().map_or(true, |uuid| &history.conn_uuid == uuid)
+			})
+			.filter(|history| !filter.failed_only || !history.success)
+			.filter(|history| history.statement.contains(&filter.keyword))
+			.cloned()
+			.collect())
+	}
+
+	fn query_history(&self, id: i64) -> IResult<DdlHistory> {
+		let histories = self.histories.lock().unwrap();
+		histories
+			.iter()
+			.find(|history| history.id == id)
+			.cloned()
+			.ok_or(IError::NotFoundError(format!("history {}", id)))
+	}
+
+	fn save_history(&self, history: &DdlHistory) -> IResult<i64> {
+		let mut histories = self.histories.lock().unwrap();
+		let id = histories.len() as i64 + 1;
+		histories.push(DdlHistory { id, ..history.clone() });
+		Ok(id)
+	}
+
+	fn save_snapshot(&self, snapshot: &Snapshot) -> IResult<i64> {
+		let mut snapshots = self.snapshots.lock().unwrap();
+		let id = snapshots.len() as i64 + 1;
+		snapshots.push(Snapshot { id, ..snapshot.clone() });
+		Ok(id)
+	}
+
+	fn query_snapshots(
+		&self,
+		conn_uuid: &str,
+		database: &str,
+		table: &str,
+	) -> IResult<Vec<Snapshot>> {
+		let snapshots = self.snapshots.lock().unwrap();
+		Ok(snapshots
+			.iter()
+			.rev()
+			.filter(|snapshot| snapshot.conn_uuid == conn_uuid)
+			.filter(|snapshot| snapshot.database == database && snapshot.table == table)
+			.cloned()
+			.collect())
+	}
+
+	fn settings(&self) -> Settings {
+		self.settings.lock().unwrap().clone()
+	}
+
+	fn save_settings(&self, settings: &Settings) -> IResult<()> {
+		*self.settings.lock().unwrap() = settings.clone();
+		Ok(())
+	}
+
+	fn file_exists(&self, path: &Path) -> bool {
+		self.files.lock().unwrap().contains_key(path)
+	}
+
+	fn create_file(&self, path: &Path, content: &str) -> IResult<()> {
+		let mut files = self.files.lock().unwrap();
+		if files.contains_key(path) {
+			return Err(io::Error::from(io::ErrorKind::AlreadyExists).into());
+		}
+		files.insert(path.to_owned(), content.to_owned());
+		Ok(())
+	}
+}
@@ -1,39 +1,200 @@
-use std::rc::Rc;
+use std::{fmt::Debug, path::Path};
 
-use once_cell::unsync::Lazy;
+use once_cell::sync::Lazy;
 use rusqlite::Connection;
 
-use crate::{error::IResult, util::app_db_file};
+use crate::{
+	conf::{self, Settings},
+	error::{IError, IResult},
+	util::{self, app_db_file},
+};
+
+use self::{
+	conn_conf::ConnConf,
+	ddl_history::{DdlHistory, HistoryFilter},
+	pool::{PooledConn, StorePool},
+	snapshot::Snapshot,
+};
 
 pub mod conn_conf;
+pub mod ddl_history;
+#[cfg(test)]
+pub mod memory;
+mod pool;
+pub mod snapshot;
+
+const MAX_IDLE_CONNECTIONS: usize = 4;
 
-const APP_TABLES: [(&'static str, &'static str); 1] = [(
-	"t_conn_conf",
-	"CREATE TABLE t_conn_conf (
+const APP_TABLES: [(&'static str, &'static str); 3] = [
+	(
+		"t_conn_conf",
+		"CREATE TABLE t_conn_conf (
 		uuid  TEXT PRIMARY KEY,
 		name TEXT NOT NULL,
 		type  TEXT NOT NULL,
 		url  TEXT NOT NULL,
 		username  TEXT,
-		password  TEXT
+		password  TEXT,
+		production  INTEGER NOT NULL DEFAULT 0
+	)",
+	),
+	(
+		"t_ddl_history",
+		"CREATE TABLE t_ddl_history (
+		id  INTEGER PRIMARY KEY AUTOINCREMENT,
+		executed_at  TEXT NOT NULL,
+		executor  TEXT NOT NULL,
+		conn_uuid  TEXT NOT NULL,
+		database  TEXT NOT NULL,
+		table_name  TEXT NOT NULL,
+		statement  TEXT NOT NULL,
+		duration_ms  INTEGER NOT NULL,
+		success  INTEGER NOT NULL,
+		message  TEXT NOT NULL
+	)",
+	),
+	(
+		"t_table_snapshot",
+		"CREATE TABLE t_table_snapshot (
+		id  INTEGER PRIMARY KEY AUTOINCREMENT,
+		taken_at  TEXT NOT NULL,
+		conn_uuid  TEXT NOT NULL,
+		database  TEXT NOT NULL,
+		table_name  TEXT NOT NULL,
+		content  TEXT NOT NULL
 	)",
-)];
+	),
+];
+
+/// Indexes created on existing databases too, as `CREATE INDEX IF NOT EXISTS` statements.
+const APP_INDEXES: [&'static str; 1] = ["CREATE INDEX IF NOT EXISTS idx_table_snapshot_table \
+	ON t_table_snapshot(conn_uuid, database, table_name)"];
+
+/// Columns added after their table was first released, as `(table, column, definition)`.
+const APP_COLUMNS: [(&'static str, &'static str, &'static str); 1] =
+	[("t_conn_conf", "production", "INTEGER NOT NULL DEFAULT 0")];
+
+/// What the GUI reads and writes in the app directory: connections, DDL history, table snapshots,
+/// settings and saved files, so that it can run against an in-memory store.
+pub trait Store: Debug + Send + Sync {
+	fn list_conns(&self) -> IResult<Vec<ConnConf>>;
+
+	fn save_conn(&self, conf: &ConnConf) -> IResult<()>;
 
-//? If return type is something like `Result<>`, err may be cached?
-pub fn get_conn<'a>() -> Rc<Connection> {
-	thread_local! {
-		static CONN: Lazy<Rc<Connection>> = Lazy::new(|| {
-			Rc::new(Connection::open(app_db_file().as_path().clone()).unwrap())
-		})
+	fn delete_conn(&self, uuid: &String) -> IResult<()>;
+
+	fn query_conn(&self, uuid: &String) -> IResult<ConnConf>;
+
+	fn find_conn(&self, name_or_uuid: &str) -> IResult<ConnConf> {
+		self.list_conns()?
+			.into_iter()
+			.find(|conf| conf.uuid == name_or_uuid || conf.name == name_or_uuid)
+			.ok_or(IError::NotFoundError(format!("connection {}", name_or_uuid)))
 	}
 
-	CONN.with(|conn| (*conn).clone())
+	fn query_histories(&self, filter: &HistoryFilter) -> IResult<Vec<DdlHistory>>;
+
+	fn query_history(&self, id: i64) -> IResult<DdlHistory>;
+
+	fn save_history(&self, history: &DdlHistory) -> IResult<i64>;
+
+	fn save_snapshot(&self, snapshot: &Snapshot) -> IResult<i64>;
+
+	/// Snapshots of a table, newest first.
+	fn query_snapshots(
+		&self,
+		conn_uuid: &str,
+		database: &str,
+		table: &str,
+	) -> IResult<Vec<Snapshot>>;
+
+	fn settings(&self) -> Settings;
+
+	fn save_settings(&self, settings: &Settings) -> IResult<()>;
+
+	fn file_exists(&self, path: &Path) -> bool;
+
+	/// Write a new file, fails rather than overwriting an existing one.
+	fn create_file(&self, path: &Path, content: &str) -> IResult<()>;
+}
+
+/// The app database, settings file and directory.
+#[derive(Debug, Default)]
+pub struct SqliteStore;
+
+impl Store for SqliteStore {
+	fn list_conns(&self) -> IResult<Vec<ConnConf>> {
+		conn_conf::list_all()
+	}
+
+	fn save_conn(&self, conf: &ConnConf) -> IResult<()> {
+		conn_conf::insert_or_update(conf)
+	}
+
+	fn delete_conn(&self, uuid: &String) -> IResult<()> {
+		conn_conf::delete(uuid)
+	}
+
+	fn query_conn(&self, uuid: &String) -> IResult<ConnConf> {
+		conn_conf::query_by_uuid(uuid)
+	}
+
+	fn query_histories(&self, filter: &HistoryFilter) -> IResult<Vec<DdlHistory>> {
+		ddl_history::query(filter)
+	}
+
+	fn query_history(&self, id: i64) -> IResult<DdlHistory> {
+		ddl_history::query_by_id(id)
+	}
+
+	fn save_history(&self, history: &DdlHistory) -> IResult<i64> {
+		ddl_history::insert(history)
+	}
+
+	fn save_snapshot(&self, snapshot: &Snapshot) -> IResult<i64> {
+		snapshot::insert(snapshot)
+	}
+
+	fn query_snapshots(
+		&self,
+		conn_uuid: &str,
+		database: &str,
+		table: &str,
+	) -> IResult<Vec<Snapshot>> {
+		snapshot::query(conn_uuid, database, table)
+	}
+
+	fn settings(&self) -> Settings {
+		conf::settings()
+	}
+
+	fn save_settings(&self, settings: &Settings) -> IResult<()> {
+		conf::update_settings(|current| *current = settings.clone())
+	}
+
+	fn file_exists(&self, path: &Path) -> bool {
+		util::exists(path)
+	}
+
+	fn create_file(&self, path: &Path, content: &str) -> IResult<()> {
+		Ok(util::write_new_file(path, content)?)
+	}
+}
+
+/// Borrow a connection of the app store, it can be used from any thread.
+pub fn get_conn() -> IResult<PooledConn> {
+	static POOL: Lazy<StorePool> =
+		Lazy::new(|| StorePool::new(app_db_file().clone(), MAX_IDLE_CONNECTIONS));
+
+	POOL.get()
 }
 
 /// Create tables if necessary.
 pub fn init_db_if_needed() -> IResult<()> {
-	let conn = get_conn();
+	create_tables(&get_conn()?)
+}
 
+fn create_tables(conn: &Connection) -> IResult<()> {
 	let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type='table'")?;
 	let existing_tables = stmt
 		.query_map([], |row| Ok(row.get::<usize, String>(0)?))?
@@ -46,6 +207,29 @@ pub fn init_db_if_needed() -> IResult<()> {
 		}
 	}
 
+	add_missing_columns(conn)?;
+	for index in APP_INDEXES {
+		conn.execute(index, ())?;
+	}
+
+	Ok(())
+}
+
+/// Bring tables created by older versions up to date.
+fn add_missing_columns(conn: &Connection) -> IResult<()> {
+	for (table, column, definition) in APP_COLUMNS {
+		let mut stmt = conn.prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))?;
+		let columns = stmt
+			.query_map([], |row| row.get::<usize, String>(0))?
+			.collect::<Result<Vec<_>, _>>()?;
+		if !columns.iter().any(|name| name == column) {
+			conn.execute(
+				&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+				(),
+			)?;
+		}
+	}
+
 	Ok(())
 }
 
@@ -53,13 +237,58 @@ pub fn init_db_if_needed() -> IResult<()> {
 mod tests {
 	use std::{
 		rc::Rc,
+		thread,
 		time::{SystemTime, UNIX_EPOCH},
 	};
 
 	use once_cell::unsync::Lazy;
 
+	use super::{add_missing_columns, pool::StorePool};
 	use crate::error::{IError, IResult};
 
+	#[test]
+	fn test_pool_concurrent_write() {
+		let path = std::env::temp_dir().join(format!("store_pool_{}.db", uuid::Uuid::new_v4()));
+		let pool = StorePool::new(path.clone(), 2);
+		pool.get().unwrap().execute("CREATE TABLE t_test (id INTEGER)", ()).unwrap();
+
+		let handles = (0..8)
+			.map(|i| {
+				let pool = pool.clone();
+				thread::spawn(move || {
+					pool.get()
+						.unwrap()
+						.execute("INSERT INTO t_test(id) VALUES (?1)", (i,))
+						.unwrap();
+				})
+			})
+			.collect::<Vec<_>>();
+		handles.into_iter().for_each(|handle| handle.join().unwrap());
+
+		let count: i64 = pool
+			.get()
+			.unwrap()
+			.query_row("SELECT count(*) FROM t_test", [], |row| row.get(0))
+			.unwrap();
+		assert_eq!(count, 8);
+		let _ = std::fs::remove_file(path);
+	}
+
+	#[test]
+	fn test_add_missing_columns() {
+		let conn = rusqlite::Connection::open_in_memory().unwrap();
+		conn.execute("CREATE TABLE t_conn_conf (uuid TEXT PRIMARY KEY, name TEXT NOT NULL)", ())
+			.unwrap();
+		conn.execute("INSERT INTO t_conn_conf(uuid, name) VALUES ('1', 'local')", ()).unwrap();
+
+		add_missing_columns(&conn).unwrap();
+		add_missing_columns(&conn).unwrap();
+		let production: bool = conn
+			.query_row("SELECT production FROM t_conn_conf WHERE uuid = '1'", [], |row| row.get(0))
+			.unwrap();
+		assert!(!production);
+	}
+
 	#[test]
 	fn test_thread_local() {
 		thread_local! {
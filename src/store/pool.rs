@@ -0,0 +1,78 @@
+use std::{
+	ops::Deref,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use rusqlite::Connection;
+
+use crate::error::IResult;
+
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A small `Send + Sync` pool of sqlite connections, connections are opened lazily and returned
+/// to the pool when the [`PooledConn`] is dropped.
+#[derive(Debug, Clone)]
+pub struct StorePool {
+	inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+	path: PathBuf,
+	max_idle: usize,
+	idle: Mutex<Vec<Connection>>,
+}
+
+impl StorePool {
+	pub fn new(path: PathBuf, max_idle: usize) -> StorePool {
+		StorePool { inner: Arc::new(PoolInner { path, max_idle, idle: Mutex::new(Vec::new()) }) }
+	}
+
+	pub fn get(&self) -> IResult<PooledConn> {
+		let idle = self.inner.idle.lock().unwrap().pop();
+		let conn = match idle {
+			Some(conn) => conn,
+			None => self.open()?,
+		};
+
+		Ok(PooledConn { conn: Some(conn), pool: self.clone() })
+	}
+
+	fn open(&self) -> IResult<Connection> {
+		let conn = Connection::open(&self.inner.path)?;
+		conn.busy_timeout(BUSY_TIMEOUT)?;
+		conn.pragma_update(None, "journal_mode", "WAL")?;
+		conn.pragma_update(None, "synchronous", "NORMAL")?;
+		Ok(conn)
+	}
+
+	fn release(&self, conn: Connection) {
+		let mut idle = self.inner.idle.lock().unwrap();
+		if idle.len() < self.inner.max_idle {
+			idle.push(conn);
+		}
+	}
+}
+
+pub struct PooledConn {
+	conn: Option<Connection>,
+	pool: StorePool,
+}
+
+impl Deref for PooledConn {
+	type Target = Connection;
+
+	fn deref(&self) -> &Self::Target {
+		self.conn.as_ref().unwrap()
+	}
+}
+
+impl Drop for PooledConn {
+	fn drop(&mut self) {
+		if let Some(conn) = self.conn.take() {
+			self.pool.release(conn);
+		}
+	}
+}
@@ -0,0 +1,173 @@
+use chrono::Local;
+use rusqlite::{Connection, Row};
+
+use crate::{error::IResult, mapping::table::Table};
+
+use super::{get_conn, Store};
+
+const SELECT_COLUMNS: &'static str =
+	"SELECT id, taken_at, conn_uuid, database, table_name, content FROM t_table_snapshot";
+
+/// A table as it was at `taken_at`.
+#[derive(Debug, Default, Clone)]
+pub struct Snapshot {
+	pub id: i64,
+	pub taken_at: String,
+	pub conn_uuid: String,
+	pub database: String,
+	pub table: String,
+	/// Canonical JSON of the `Table`, equal tables have equal contents.
+	pub content: String,
+}
+
+impl Snapshot {
+	pub fn new(conn_uuid: &str, table: &Table) -> IResult<Snapshot> {
+		Ok(Snapshot {
+			taken_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+			conn_uuid: conn_uuid.to_owned(),
+			database: table.database.clone(),
+			table: table.name.clone(),
+			content: to_json(table)?,
+			..Default::default()
+		})
+	}
+
+	pub fn table(&self) -> IResult<Table> {
+		from_json(&self.content)
+	}
+
+	fn from_row(row: &Row) -> rusqlite::Result<Snapshot> {
+		Ok(Snapshot {
+			id: row.get(0)?,
+			taken_at: row.get(1)?,
+			conn_uuid: row.get(2)?,
+			database: row.get(3)?,
+			table: row.get(4)?,
+			content: row.get(5)?,
+		})
+	}
+}
+
+/// Store a snapshot of `table` unless it equals the latest one, returns the stored snapshot.
+pub fn take(store: &dyn Store, conn_uuid: &str, table: &Table) -> IResult<Option<Snapshot>> {
+	let mut snapshot = Snapshot::new(conn_uuid, table)?;
+	let snapshots = store.query_snapshots(conn_uuid, &table.database, &table.name)?;
+	if snapshots.first().map_or(false, |latest| latest.content == snapshot.content) {
+		return Ok(None);
+	}
+
+	snapshot.id = store.save_snapshot(&snapshot)?;
+	Ok(Some(snapshot))
+}
+
+pub fn insert(snapshot: &Snapshot) -> IResult<i64> {
+	insert_into(&get_conn()?, snapshot)
+}
+
+fn insert_into(conn: &Connection, snapshot: &Snapshot) -> IResult<i64> {
+	conn.execute(
+		"INSERT INTO t_table_snapshot(taken_at, conn_uuid, database, table_name, content) \
+		VALUES (?1, ?2, ?3, ?4, ?5)",
+		(
+			&snapshot.taken_at,
+			&snapshot.conn_uuid,
+			&snapshot.database,
+			&snapshot.table,
+			&snapshot.content,
+		),
+	)?;
+
+	Ok(conn.last_insert_rowid())
+}
+
+/// Snapshots of a table, newest first.
+pub fn query(conn_uuid: &str, database: &str, table: &str) -> IResult<Vec<Snapshot>> {
+	query_from(&get_conn()?, conn_uuid, database, table)
+}
+
+fn query_from(
+	conn: &Connection,
+	conn_uuid: &str,
+	database: &str,
+	table: &str,
+) -> IResult<Vec<Snapshot>> {
+	let mut stmt = conn.prepare(&format!(
+		"{} WHERE conn_uuid = ?1 AND database = ?2 AND table_name = ?3 ORDER BY id DESC",
+		SELECT_COLUMNS
+	))?;
+	let list = stmt
+		.query_map((conn_uuid, database, table), Snapshot::from_row)?
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(list)
+}
+
+#[cfg(feature = "json")]
+fn to_json(table: &Table) -> IResult<String> {
+	Ok(serde_json::to_string(table)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn to_json(_: &Table) -> IResult<String> {
+	Err(crate::error::IError::PromptError("Snapshots require the `json` feature".to_owned()))
+}
+
+#[cfg(feature = "json")]
+fn from_json(content: &str) -> IResult<Table> {
+	Ok(serde_json::from_str(content)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn from_json(_: &str) -> IResult<Table> {
+	Err(crate::error::IError::PromptError("Snapshots require the `json` feature".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+	use rusqlite::Connection;
+
+	use super::{insert_into, query_from, Snapshot};
+	use crate::store::create_tables;
+
+	fn snapshot(table: &str, content: &str) -> Snapshot {
+		Snapshot {
+			taken_at: "2023-01-01 00:00:00".to_owned(),
+			conn_uuid: "local".to_owned(),
+			database: "shop".to_owned(),
+			table: table.to_owned(),
+			content: content.to_owned(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_insert_and_query() {
+		let path = std::env::temp_dir().join(format!("store_snapshot_{}.db", uuid::Uuid::new_v4()));
+		let conn = Connection::open(&path).unwrap();
+		create_tables(&conn).unwrap();
+		create_tables(&conn).unwrap();
+
+		let first = insert_into(&conn, &snapshot("user", "v1")).unwrap();
+		insert_into(&conn, &snapshot("order", "v1")).unwrap();
+		let second = insert_into(&conn, &snapshot("user", "v2")).unwrap();
+
+		let snapshots = query_from(&conn, "local", "shop", "user").unwrap();
+		let ids = snapshots.iter().map(|snapshot| snapshot.id).collect::<Vec<_>>();
+		assert_eq!(ids, vec![second, first]);
+		assert_eq!(snapshots[0].content, "v2");
+		assert_eq!(snapshots[0].table, "user");
+		assert!(query_from(&conn, "other", "shop", "user").unwrap().is_empty());
+
+		let plan: String = conn
+			.query_row(
+				"EXPLAIN QUERY PLAN SELECT id FROM t_table_snapshot \
+				WHERE conn_uuid = 'local' AND database = 'shop' AND table_name = 'user'",
+				[],
+				|row| row.get(3),
+			)
+			.unwrap();
+		assert!(plan.contains("idx_table_snapshot_table"), "{}", plan);
+		drop(conn);
+		let _ = std::fs::remove_file(path);
+	}
+}
@@ -1,5 +1,5 @@
 use std::{
-	fs::{self, File},
+	fs::{self, File, OpenOptions},
 	io,
 	path::{Path, PathBuf},
 };
@@ -9,13 +9,29 @@ use once_cell::sync::OnceCell;
 
 const APP_ROOT_PATH: &'static str = ".schema_syncr";
 
+/// Environment variable used to relocate the application directory.
+pub const APP_HOME_ENV: &'static str = "SCHEMA_SYNCR_HOME";
+
 const APP_DATA_FILE: &'static str = "data.db";
 
 const APP_LOG_FILE: &'static str = "logs/app.log";
 
+const APP_SETTINGS_FILE: &'static str = "settings.toml";
+
+const APP_DDL_DIR: &'static str = "ddl";
+
+static ROOT_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Override the application directory, must be called before any path is resolved.
+pub fn set_app_root_dir(path: PathBuf) -> bool {
+	ROOT_DIR.set(path).is_ok()
+}
+
 pub fn app_root_dir() -> &'static PathBuf {
-	static ROOT_DIR: OnceCell<PathBuf> = OnceCell::new();
-	ROOT_DIR.get_or_init(|| UserDirs::new().unwrap().home_dir().join(APP_ROOT_PATH))
+	ROOT_DIR.get_or_init(|| match std::env::var(APP_HOME_ENV) {
+		Ok(home) if !home.is_empty() => PathBuf::from(home),
+		_ => UserDirs::new().unwrap().home_dir().join(APP_ROOT_PATH),
+	})
 }
 
 pub fn app_db_file() -> &'static PathBuf {
@@ -28,6 +44,17 @@ pub fn app_log_file() -> &'static PathBuf {
 	LOG_FILE.get_or_init(|| app_root_dir().join(APP_LOG_FILE))
 }
 
+pub fn app_settings_file() -> &'static PathBuf {
+	static SETTINGS_FILE: OnceCell<PathBuf> = OnceCell::new();
+	SETTINGS_FILE.get_or_init(|| app_root_dir().join(APP_SETTINGS_FILE))
+}
+
+/// Directory where DDL previews are saved as `.sql` files.
+pub fn app_ddl_dir() -> &'static PathBuf {
+	static DDL_DIR: OnceCell<PathBuf> = OnceCell::new();
+	DDL_DIR.get_or_init(|| app_root_dir().join(APP_DDL_DIR))
+}
+
 pub fn exists(path: &Path) -> bool {
 	Path::new(path).exists()
 }
@@ -40,6 +67,37 @@ pub fn create_if_not_exist(path: &Path) -> io::Result<()> {
 	Ok(())
 }
 
+/// Write content to path, creating parent directories when needed.
+pub fn write_file(path: &Path, content: &str) -> io::Result<()> {
+	io::Write::write_all(&mut create_file(path)?, content.as_bytes())
+}
+
+/// Write content to a new file at path, fails if it exists.
+pub fn write_new_file(path: &Path, content: &str) -> io::Result<()> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?
+	}
+
+	let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+	io::Write::write_all(&mut file, content.as_bytes())
+}
+
+/// Last `max_lines` lines of a text file, only its tail is read.
+pub fn tail_lines(path: &Path, max_lines: usize) -> io::Result<Vec<String>> {
+	const TAIL_BYTES: u64 = 256 * 1024;
+
+	let mut file = File::open(path)?;
+	let offset = file.metadata()?.len().saturating_sub(TAIL_BYTES);
+	io::Seek::seek(&mut file, io::SeekFrom::Start(offset))?;
+	let mut bytes = Vec::new();
+	io::Read::read_to_end(&mut file, &mut bytes)?;
+
+	let content = String::from_utf8_lossy(&bytes);
+	// The first line is likely cut when reading from the middle.
+	let lines = content.lines().skip(if offset > 0 { 1 } else { 0 }).collect::<Vec<_>>();
+	Ok(lines[lines.len().saturating_sub(max_lines)..].iter().map(|line| line.to_string()).collect())
+}
+
 fn create_file(path: &Path) -> io::Result<File> {
 	if let Some(parent) = path.parent() {
 		fs::create_dir_all(parent)?
@@ -48,3 +106,18 @@ fn create_file(path: &Path) -> io::Result<File> {
 	let file = File::create(path)?;
 	Ok(file)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{tail_lines, write_file};
+
+	#[test]
+	fn test_tail_lines() {
+		let path = std::env::temp_dir()
+			.join(format!("schema_syncr_tail_lines_{}.log", uuid::Uuid::new_v4()));
+		write_file(&path, "a\nb\nc\n").unwrap();
+		assert_eq!(tail_lines(&path, 2).unwrap(), vec!["b", "c"]);
+		assert_eq!(tail_lines(&path, 10).unwrap(), vec!["a", "b", "c"]);
+		std::fs::remove_file(&path).unwrap();
+	}
+}